@@ -0,0 +1,230 @@
+// air.rs — AIR arithmetization: an execution trace (columns over rows), transition
+// constraints as polynomial expressions over the current and next row, and boundary
+// constraints pinning single cells. stark.rs proves that a trace satisfies an `Air`.
+//
+// Transitions must vanish on every pair of consecutive rows (row i, row i + 1) for
+// i < num_rows - 1; there is no wrap-around from the last row to the first.
+
+use crate::field::{ExtensionField, PrimeField};
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceTable<F: PrimeField> {
+    // columns[c][row]
+    pub columns: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> TraceTable<F> {
+    pub fn new(width: usize, num_rows: usize) -> Self { Self { columns: vec![vec![F::ZERO; num_rows]; width] } }
+    // from a list of rows
+    pub fn from_rows(rows: &[Vec<F>]) -> Self {
+        let width = rows.first().map_or(0, |r| r.len());
+        Self { columns: (0..width).map(|c| rows.iter().map(|r| r[c]).collect()).collect() }
+    }
+    pub fn width(&self) -> usize { self.columns.len() }
+    pub fn num_rows(&self) -> usize { self.columns.first().map_or(0, |c| c.len()) }
+    pub fn get(&self, column: usize, row: usize) -> F { self.columns[column][row] }
+    pub fn set(&mut self, column: usize, row: usize, value: F) { self.columns[column][row] = value; }
+    pub fn row(&self, row: usize) -> Vec<F> { self.columns.iter().map(|c| c[row]).collect() }
+}
+
+// polynomial expression over the cells of the current and next row
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr<F: PrimeField> {
+    Const(F),
+    Cur(usize),
+    Next(usize),
+    Add(Box<Expr<F>>, Box<Expr<F>>),
+    Sub(Box<Expr<F>>, Box<Expr<F>>),
+    Mul(Box<Expr<F>>, Box<Expr<F>>),
+    Neg(Box<Expr<F>>),
+    Pow(Box<Expr<F>>, u64),
+}
+
+impl<F: PrimeField> Expr<F> {
+    pub fn cur(column: usize) -> Self { Expr::Cur(column) }
+    pub fn next(column: usize) -> Self { Expr::Next(column) }
+    pub fn constant(c: F) -> Self { Expr::Const(c) }
+    pub fn pow(self, e: u64) -> Self { Expr::Pow(Box::new(self), e) }
+
+    // over F for the trace itself, over an extension at the STARK's out-of-domain point
+    pub fn eval<E: ExtensionField<F>>(&self, cur: &[E], next: &[E]) -> E {
+        match self {
+            Expr::Const(c) => E::from_base(*c),
+            Expr::Cur(i) => cur[*i],
+            Expr::Next(i) => next[*i],
+            Expr::Add(a, b) => a.eval(cur, next) + b.eval(cur, next),
+            Expr::Sub(a, b) => a.eval(cur, next) - b.eval(cur, next),
+            Expr::Mul(a, b) => a.eval(cur, next) * b.eval(cur, next),
+            Expr::Neg(a) => -a.eval(cur, next),
+            Expr::Pow(a, e) => a.eval(cur, next).exp(*e),
+        }
+    }
+
+    // total degree in the trace cells (an upper bound; cancellations are not detected)
+    pub fn degree(&self) -> usize {
+        match self {
+            Expr::Const(_) => 0,
+            Expr::Cur(_) | Expr::Next(_) => 1,
+            Expr::Add(a, b) | Expr::Sub(a, b) => a.degree().max(b.degree()),
+            Expr::Mul(a, b) => a.degree() + b.degree(),
+            Expr::Neg(a) => a.degree(),
+            Expr::Pow(a, e) => a.degree() * *e as usize,
+        }
+    }
+
+    // largest column index referenced, if any
+    fn max_column(&self) -> Option<usize> {
+        match self {
+            Expr::Const(_) => None,
+            Expr::Cur(i) | Expr::Next(i) => Some(*i),
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) => a.max_column().max(b.max_column()),
+            Expr::Neg(a) | Expr::Pow(a, _) => a.max_column(),
+        }
+    }
+}
+
+impl<F: PrimeField> Add for Expr<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self { Expr::Add(Box::new(self), Box::new(rhs)) }
+}
+impl<F: PrimeField> Sub for Expr<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self { Expr::Sub(Box::new(self), Box::new(rhs)) }
+}
+impl<F: PrimeField> Mul for Expr<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self { Expr::Mul(Box::new(self), Box::new(rhs)) }
+}
+impl<F: PrimeField> Neg for Expr<F> {
+    type Output = Self;
+    fn neg(self) -> Self { Expr::Neg(Box::new(self)) }
+}
+
+// trace[column][row] = value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Boundary<F: PrimeField> {
+    pub column: usize,
+    pub row: usize,
+    pub value: F,
+}
+
+#[derive(Clone, Debug)]
+pub struct Air<F: PrimeField> {
+    pub width: usize,
+    // a power of two
+    pub num_rows: usize,
+    pub transitions: Vec<Expr<F>>,
+    pub boundaries: Vec<Boundary<F>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AirError {
+    Shape,
+    Transition { constraint: usize, row: usize },
+    Boundary(usize),
+}
+
+impl fmt::Display for AirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AirError::Shape => write!(f, "trace or constraints do not match the AIR dimensions"),
+            AirError::Transition { constraint, row } => write!(f, "transition {constraint} fails between rows {row} and {}", row + 1),
+            AirError::Boundary(i) => write!(f, "boundary constraint {i} fails"),
+        }
+    }
+}
+
+impl std::error::Error for AirError {}
+
+impl<F: PrimeField> Air<F> {
+    pub fn new(width: usize, num_rows: usize) -> Self {
+        assert!(num_rows.is_power_of_two() && num_rows >= 2, "trace length {num_rows} must be a power of two >= 2");
+        Self { width, num_rows, transitions: vec![], boundaries: vec![] }
+    }
+    pub fn transition(mut self, e: Expr<F>) -> Self { self.transitions.push(e); self }
+    pub fn boundary(mut self, column: usize, row: usize, value: F) -> Self {
+        self.boundaries.push(Boundary { column, row, value });
+        self
+    }
+
+    pub fn max_degree(&self) -> usize { self.transitions.iter().map(|e| e.degree()).max().unwrap_or(1).max(1) }
+
+    // constraints only reference existing columns and rows
+    pub fn check_shape(&self) -> Result<(), AirError> {
+        let cols_ok = self.transitions.iter().all(|e| e.max_column().is_none_or(|c| c < self.width));
+        let bounds_ok = self.boundaries.iter().all(|b| b.column < self.width && b.row < self.num_rows);
+        if cols_ok && bounds_ok { Ok(()) } else { Err(AirError::Shape) }
+    }
+
+    pub fn check_trace(&self, trace: &TraceTable<F>) -> Result<(), AirError> {
+        self.check_shape()?;
+        if trace.width() != self.width || trace.num_rows() != self.num_rows {
+            return Err(AirError::Shape);
+        }
+        for row in 0..self.num_rows - 1 {
+            let (cur, next) = (trace.row(row), trace.row(row + 1));
+            if let Some(i) = self.transitions.iter().position(|e| !e.eval(&cur, &next).is_zero()) {
+                return Err(AirError::Transition { constraint: i, row });
+            }
+        }
+        match self.boundaries.iter().position(|b| trace.get(b.column, b.row) != b.value) {
+            Some(i) => Err(AirError::Boundary(i)),
+            None => Ok(()),
+        }
+    }
+}
+
+// ---- Example: iterated `poseidon_round` ----
+// Three columns hold the state; row i + 1 = poseidon_round(row i), so num_rows rows apply
+// num_rows - 1 rounds. Each transition is degree 5 (the S-box); compare the R1CS gadget,
+// which spends 9 constraints per round.
+pub fn poseidon_round_air<F: PrimeField>(num_rows: usize, input: [F; 3], output: [F; 3]) -> Air<F> {
+    let (one, two) = (F::ONE, F::from_u64(2));
+    let mut air = Air::new(3, num_rows);
+    for (i, coeffs) in [[two, one, one], [one, two, one], [one, one, two]].iter().enumerate() {
+        let mixed = coeffs.iter().enumerate().fold(Expr::constant(F::ZERO), |acc, (j, m)| {
+            acc + Expr::constant(*m) * Expr::cur(j).pow(5)
+        });
+        air = air.transition(Expr::next(i) - mixed);
+    }
+    for i in 0..3 {
+        air = air.boundary(i, 0, input[i]).boundary(i, num_rows - 1, output[i]);
+    }
+    air
+}
+
+pub fn poseidon_round_trace<F: PrimeField>(num_rows: usize, input: [F; 3]) -> TraceTable<F> {
+    let rows: Vec<Vec<F>> = std::iter::successors(Some(input), |s| {
+        let mut s = *s;
+        crate::poseidon_round(&mut s);
+        Some(s)
+    }).take(num_rows).map(|s| s.to_vec()).collect();
+    TraceTable::from_rows(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Goldilocks;
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+
+    #[test]
+    fn poseidon_round_trace_satisfies_air() {
+        let input = [f(1), f(2), f(3)];
+        let trace = poseidon_round_trace(8, input);
+        let output: [Goldilocks; 3] = trace.row(7).try_into().unwrap();
+        let air = poseidon_round_air(8, input, output);
+        assert_eq!(air.max_degree(), 5);
+        assert_eq!(air.check_trace(&trace), Ok(()));
+
+        let mut bad = trace.clone();
+        bad.set(1, 4, bad.get(1, 4) + f(1));
+        assert_eq!(air.check_trace(&bad), Err(AirError::Transition { constraint: 1, row: 3 }));
+        let wrong_out = poseidon_round_air(8, input, [f(0), output[1], output[2]]);
+        assert_eq!(wrong_out.check_trace(&trace), Err(AirError::Boundary(1)));
+        assert_eq!(air.check_trace(&poseidon_round_trace(4, input)), Err(AirError::Shape));
+    }
+}
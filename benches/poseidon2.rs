@@ -0,0 +1,38 @@
+// benches/poseidon2.rs — Poseidon vs Poseidon2 over Goldilocks: R1CS cost of the Builder
+// gadgets (constraints, non-zero matrix entries) and native permutation throughput.
+//
+// Run: cargo bench --bench poseidon2
+
+use delta_zk::poseidon::Poseidon;
+use delta_zk::poseidon2::Poseidon2;
+use delta_zk::{Builder, Goldilocks, LinComb};
+use std::time::Instant;
+
+const ITERS: u32 = 20_000;
+
+fn nonzeros(b: &Builder<Goldilocks>) -> usize {
+    b.constraints.iter().map(|c| c.a.terms.len() + c.b.terms.len() + c.c.terms.len()).sum()
+}
+
+fn report(name: &str, width: usize, gadget: impl Fn(&mut Builder<Goldilocks>, &[LinComb<Goldilocks>]), native: impl Fn(&mut [Goldilocks])) {
+    let mut b = Builder::new();
+    let inputs: Vec<LinComb<Goldilocks>> = (0..width as u64).map(|i| LinComb::var(b.alloc(Goldilocks::from_u64(i)))).collect();
+    gadget(&mut b, &inputs);
+
+    let mut state: Vec<Goldilocks> = (0..width as u64).map(Goldilocks::from_u64).collect();
+    let start = Instant::now();
+    for _ in 0..ITERS { native(&mut state); }
+    let per_perm = start.elapsed() / ITERS;
+    println!("{name:<10} t={width:<3} constraints={:<5} nonzeros={:<7} native={per_perm:?}/perm",
+             b.constraints.len(), nonzeros(&b));
+}
+
+fn main() {
+    for (p, p2) in [(Poseidon::goldilocks_t8(), Poseidon2::goldilocks_t8()),
+                    (Poseidon::goldilocks_t12(), Poseidon2::goldilocks_t12())] {
+        report("poseidon", p.width, |b, s| { b.poseidon_permutation(&p, s); }, |s| p.permute(s));
+        report("poseidon2", p2.width, |b, s| { b.poseidon2_permutation(&p2, s); }, |s| p2.permute(s));
+    }
+    let p2 = Poseidon2::goldilocks_t16();
+    report("poseidon2", 16, |b, s| { b.poseidon2_permutation(&p2, s); }, |s| p2.permute(s));
+}
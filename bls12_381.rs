@@ -0,0 +1,32 @@
+// bls12_381.rs — BLS12-381 scalar field.
+// r = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+
+montgomery_field!(
+    // BLS12-381 scalar field Fr, multiplicative generator 7.
+    Fr,
+    modulus = [0xffffffff00000001, 0x53bda402fffe5bfe, 0x3339d80809a1d805, 0x73eda753299d7d48],
+    r = [0x00000001fffffffe, 0x5884b7fa00034802, 0x998c4fefecbc4ff5, 0x1824b159acc5056f],
+    r2 = [0xc999e990f3f29c6d, 0x2b6cedcb87925c23, 0x05d314967254398f, 0x0748d9d99f59ff11],
+    inv = 0xfffffffeffffffff,
+    generator = [0x0000000efffffff1, 0x17e363d300189c0f, 0xff9c57876f8457b0, 0x351332208fc5a8c4],
+    bits = 255,
+    two_adicity = 32,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::PrimeField;
+
+    #[test]
+    fn fr_arithmetic() {
+        let minus_one = -Fr::ONE;
+        assert_eq!(minus_one * minus_one, Fr::ONE);
+        let x = Fr::from_u64(123456789);
+        assert_eq!(x * x.inverse().unwrap(), Fr::ONE);
+        assert_eq!(Fr::from_bytes_le(&x.to_bytes_le()), Some(x));
+        assert_eq!(Fr::from_bytes_le(&[0xff; 32]), None);
+        let w = Fr::two_adic_root_of_unity();
+        assert_eq!(w.pow(1 << 31), minus_one);
+    }
+}
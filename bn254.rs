@@ -0,0 +1,540 @@
+// bn254.rs — BN254 (alt_bn128), the curve behind Ethereum's pairing precompiles (EIP-196/197):
+// scalar field Fr (the field of Ethereum-facing SNARK circuits), base field Fq, the
+// Fq2/Fq6/Fq12 tower, the groups G1 / G2 and a pairing.
+// r = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+// p = 21888242871839275222246405745257275088696311157297823662689037894645226208583
+
+use crate::field::{ExtensionField, PrimeField};
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+montgomery_field!(
+    // BN254 scalar field Fr, multiplicative generator 5.
+    Fr,
+    modulus = [0x43e1f593f0000001, 0x2833e84879b97091, 0xb85045b68181585d, 0x30644e72e131a029],
+    r = [0xac96341c4ffffffb, 0x36fc76959f60cd29, 0x666ea36f7879462e, 0x0e0a77c19a07df2f],
+    r2 = [0x1bb8e645ae216da7, 0x53fe3ab1e35c59e3, 0x8c49833d53bb8085, 0x0216d0b17f4e44a5],
+    inv = 0xc2e1f593efffffff,
+    generator = [0x1b0d0ef99fffffe6, 0xeaba68a3a32a913f, 0x47d8eb76d8dd0689, 0x15d0085520f5bbc3],
+    bits = 254,
+    two_adicity = 28,
+);
+
+montgomery_field!(
+    // BN254 base field Fq, multiplicative generator 3.
+    Fq,
+    modulus = [0x3c208c16d87cfd47, 0x97816a916871ca8d, 0xb85045b68181585d, 0x30644e72e131a029],
+    r = [0xd35d438dc58f0d9d, 0x0a78eb28f5c70b3d, 0x666ea36f7879462c, 0x0e0a77c19a07df2f],
+    r2 = [0xf32cfc5b538afa89, 0xb5e71911d44501fb, 0x47ab1eff0a417ff6, 0x06d89f71cab8351f],
+    inv = 0x87d20782e4866389,
+    generator = [0x7a17caa950ad28d7, 0x1f6ac17ae15521b9, 0x334bea4e696bd284, 0x2a1f6744ce179d8e],
+    bits = 254,
+    two_adicity = 1,
+);
+
+// ---- Extension tower ----
+//   Fq2  = Fq[u] / (u^2 + 1)
+//   Fq6  = Fq2[v] / (v^3 - ξ),  ξ = 9 + u
+//   Fq12 = Fq6[w] / (w^2 - v)
+
+// component-wise Add / Sub / Neg and the assigning operators
+macro_rules! tower_ops {
+    ($name:ident, $($c:ident),+) => {
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self { Self { $($c: self.$c + rhs.$c),+ } }
+        }
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self { Self { $($c: self.$c - rhs.$c),+ } }
+        }
+        impl Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self { Self { $($c: -self.$c),+ } }
+        }
+        impl AddAssign for $name { fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; } }
+        impl SubAssign for $name { fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs; } }
+        impl MulAssign for $name { fn mul_assign(&mut self, rhs: Self) { *self = *self * rhs; } }
+    };
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Fq2 {
+    pub c0: Fq,
+    pub c1: Fq,
+}
+
+impl Fq2 {
+    pub const ZERO: Self = Self { c0: Fq::ZERO, c1: Fq::ZERO };
+    pub const ONE: Self = Self { c0: Fq::ONE, c1: Fq::ZERO };
+    pub fn new(c0: Fq, c1: Fq) -> Self { Self { c0, c1 } }
+    pub fn is_zero(&self) -> bool { *self == Self::ZERO }
+    pub fn scale(&self, k: Fq) -> Self { Self { c0: self.c0 * k, c1: self.c1 * k } }
+    // ·ξ = ·(9 + u)
+    pub fn mul_by_nonresidue(&self) -> Self {
+        let nine = |x: Fq| { let x8 = x.double().double().double(); x8 + x };
+        Self { c0: nine(self.c0) - self.c1, c1: self.c0 + nine(self.c1) }
+    }
+    pub fn inverse(&self) -> Option<Self> {
+        let norm = self.c0.square() + self.c1.square();
+        norm.inverse().map(|n| Self { c0: self.c0 * n, c1: -self.c1 * n })
+    }
+}
+
+impl Mul for Fq2 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let (v0, v1) = (self.c0 * rhs.c0, self.c1 * rhs.c1);
+        Self { c0: v0 - v1, c1: (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - v0 - v1 }
+    }
+}
+tower_ops!(Fq2, c0, c1);
+
+impl fmt::Display for Fq2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{} + {}·u", self.c0, self.c1) }
+}
+
+impl ExtensionField<Fq> for Fq2 {
+    const DEGREE: usize = 2;
+    fn from_base(x: Fq) -> Self { Self { c0: x, c1: Fq::ZERO } }
+    fn invert(&self) -> Option<Self> { self.inverse() }
+    fn to_base_coeffs(&self) -> Vec<Fq> { vec![self.c0, self.c1] }
+    fn from_base_coeffs(coeffs: &[Fq]) -> Self {
+        let [c0, c1] = coeffs.try_into().expect("wrong number of coordinates");
+        Self { c0, c1 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Fq6 {
+    pub c0: Fq2,
+    pub c1: Fq2,
+    pub c2: Fq2,
+}
+
+impl Fq6 {
+    pub const ZERO: Self = Self { c0: Fq2::ZERO, c1: Fq2::ZERO, c2: Fq2::ZERO };
+    pub const ONE: Self = Self { c0: Fq2::ONE, c1: Fq2::ZERO, c2: Fq2::ZERO };
+    // ·v
+    pub fn mul_by_v(&self) -> Self { Self { c0: self.c2.mul_by_nonresidue(), c1: self.c0, c2: self.c1 } }
+    pub fn scale(&self, k: Fq) -> Self { Self { c0: self.c0.scale(k), c1: self.c1.scale(k), c2: self.c2.scale(k) } }
+    pub fn inverse(&self) -> Option<Self> {
+        let (a0, a1, a2) = (self.c0, self.c1, self.c2);
+        let t0 = a0 * a0 - (a1 * a2).mul_by_nonresidue();
+        let t1 = (a2 * a2).mul_by_nonresidue() - a0 * a1;
+        let t2 = a1 * a1 - a0 * a2;
+        let det = a0 * t0 + (a2 * t1 + a1 * t2).mul_by_nonresidue();
+        det.inverse().map(|d| Self { c0: t0 * d, c1: t1 * d, c2: t2 * d })
+    }
+}
+
+impl Mul for Fq6 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let (a0, a1, a2) = (self.c0, self.c1, self.c2);
+        let (b0, b1, b2) = (rhs.c0, rhs.c1, rhs.c2);
+        Self {
+            c0: a0 * b0 + (a1 * b2 + a2 * b1).mul_by_nonresidue(),
+            c1: a0 * b1 + a1 * b0 + (a2 * b2).mul_by_nonresidue(),
+            c2: a0 * b2 + a1 * b1 + a2 * b0,
+        }
+    }
+}
+tower_ops!(Fq6, c0, c1, c2);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Fq12 {
+    pub c0: Fq6,
+    pub c1: Fq6,
+}
+
+impl Fq12 {
+    pub const ONE: Self = Self { c0: Fq6::ONE, c1: Fq6::ZERO };
+    pub fn from_fq(x: Fq) -> Self { Self { c0: Fq6 { c0: Fq2::from_base(x), ..Fq6::ZERO }, c1: Fq6::ZERO } }
+    pub fn scale(&self, k: Fq) -> Self { Self { c0: self.c0.scale(k), c1: self.c1.scale(k) } }
+    pub fn square(&self) -> Self { *self * *self }
+    // x^(p^6): w -> -w
+    pub fn conjugate(&self) -> Self { Self { c0: self.c0, c1: -self.c1 } }
+    pub fn inverse(&self) -> Option<Self> {
+        let norm = self.c0 * self.c0 - (self.c1 * self.c1).mul_by_v();
+        norm.inverse().map(|n| Self { c0: self.c0 * n, c1: -(self.c1 * n) })
+    }
+    // exponent as little-endian u64 limbs
+    pub fn pow_limbs(&self, e: &[u64]) -> Self {
+        let mut r = Self::ONE;
+        for limb in e.iter().rev() {
+            for i in (0..64).rev() {
+                r = r.square();
+                if (limb >> i) & 1 == 1 { r *= *self; }
+            }
+        }
+        r
+    }
+}
+
+impl Mul for Fq12 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let (v0, v1) = (self.c0 * rhs.c0, self.c1 * rhs.c1);
+        Self { c0: v0 + v1.mul_by_v(), c1: (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - v0 - v1 }
+    }
+}
+tower_ops!(Fq12, c0, c1);
+
+// ---- Groups ----
+// G1 = E(Fq): y^2 = x^3 + 3, generator (1, 2), prime order r.
+// G2 = the order-r subgroup of the D-type sextic twist E'(Fq2): y^2 = x^3 + 3/ξ, with the
+// EIP-197 generator.
+
+pub trait CurveParams: Clone + Copy + fmt::Debug + 'static {
+    type Base: ExtensionField<Fq>;
+    fn coeff_b() -> Self::Base;
+    fn generator() -> (Self::Base, Self::Base);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct G1Params;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct G2Params;
+
+fn fq(limbs: [u64; 4]) -> Fq { Fq::from_limbs(limbs).unwrap() }
+
+impl CurveParams for G1Params {
+    type Base = Fq;
+    fn coeff_b() -> Fq { Fq::from_u64(3) }
+    fn generator() -> (Fq, Fq) { (Fq::from_u64(1), Fq::from_u64(2)) }
+}
+
+impl CurveParams for G2Params {
+    type Base = Fq2;
+    fn coeff_b() -> Fq2 { Fq2::from_base(Fq::from_u64(3)) * Fq2::new(Fq::from_u64(9), Fq::ONE).inverse().unwrap() }
+    fn generator() -> (Fq2, Fq2) {
+        (
+            Fq2::new(
+                fq([0x46debd5cd992f6ed, 0x674322d4f75edadd, 0x426a00665e5c4479, 0x1800deef121f1e76]),
+                fq([0x97e485b7aef312c2, 0xf1aa493335a9e712, 0x7260bfb731fb5d25, 0x198e9393920d483a]),
+            ),
+            Fq2::new(
+                fq([0x4ce6cc0166fa7daa, 0xe3d1e7690c43d37b, 0x4aab71808dcb408f, 0x12c85ea5db8c6deb]),
+                fq([0x55acdadcd122975b, 0xbc4b313370b38ef3, 0xec9e99ad690c3395, 0x090689d0585ff075]),
+            ),
+        )
+    }
+}
+
+// Jacobian coordinates (X : Y : Z) = (X/Z^2, Y/Z^3); Z = 0 is the point at infinity.
+#[derive(Clone, Copy, Debug)]
+pub struct Point<C: CurveParams> {
+    pub x: C::Base,
+    pub y: C::Base,
+    pub z: C::Base,
+}
+
+pub type G1 = Point<G1Params>;
+pub type G2 = Point<G2Params>;
+
+impl<C: CurveParams> Point<C> {
+    pub fn identity() -> Self { Self { x: C::Base::default(), y: one::<C>(), z: C::Base::default() } }
+    pub fn generator() -> Self {
+        let (x, y) = C::generator();
+        Self { x, y, z: one::<C>() }
+    }
+    // None unless (x, y) is on the curve
+    pub fn from_affine(x: C::Base, y: C::Base) -> Option<Self> {
+        (y * y == x * x * x + C::coeff_b()).then_some(Self { x, y, z: one::<C>() })
+    }
+    pub fn is_identity(&self) -> bool { self.z == C::Base::default() }
+    pub fn to_affine(&self) -> Option<(C::Base, C::Base)> {
+        let zi = self.z.invert()?;
+        let zi2 = zi * zi;
+        Some((self.x * zi2, self.y * zi2 * zi))
+    }
+    pub fn is_on_curve(&self) -> bool {
+        match self.to_affine() {
+            Some((x, y)) => y * y == x * x * x + C::coeff_b(),
+            None => true,
+        }
+    }
+    // r·P = O (G1 has cofactor 1, so this only matters for G2)
+    pub fn is_in_subgroup(&self) -> bool { self.mul_limbs(Fr::MODULUS).is_identity() }
+
+    // dbl-2009-l
+    pub fn double(&self) -> Self {
+        if self.is_identity() { return *self; }
+        let a = self.x * self.x;
+        let b = self.y * self.y;
+        let c = b * b;
+        let t = self.x + b;
+        let d = (t * t - a - c).double_ext();
+        let e = a.double_ext() + a;
+        let x3 = e * e - d.double_ext();
+        let c8 = c.double_ext().double_ext().double_ext();
+        Self { x: x3, y: e * (d - x3) - c8, z: (self.y * self.z).double_ext() }
+    }
+
+    // little-endian u64 limbs
+    pub fn mul_limbs(&self, k: &[u64]) -> Self {
+        let mut acc = Self::identity();
+        for limb in k.iter().rev() {
+            for i in (0..64).rev() {
+                acc = acc.double();
+                if (limb >> i) & 1 == 1 { acc = acc + *self; }
+            }
+        }
+        acc
+    }
+    pub fn mul(&self, k: &Fr) -> Self { self.mul_limbs(&k.to_limbs()) }
+}
+
+fn one<C: CurveParams>() -> C::Base { C::Base::from_base(Fq::ONE) }
+
+// x + x, for coordinates in any extension of Fq
+trait DoubleExt { fn double_ext(self) -> Self; }
+impl<E: ExtensionField<Fq>> DoubleExt for E {
+    fn double_ext(self) -> Self { self + self }
+}
+
+// add-2007-bl
+impl<C: CurveParams> Add for Point<C> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        if self.is_identity() { return rhs; }
+        if rhs.is_identity() { return self; }
+        let z1z1 = self.z * self.z;
+        let z2z2 = rhs.z * rhs.z;
+        let u1 = self.x * z2z2;
+        let u2 = rhs.x * z1z1;
+        let s1 = self.y * rhs.z * z2z2;
+        let s2 = rhs.y * self.z * z1z1;
+        if u1 == u2 {
+            return if s1 == s2 { self.double() } else { Self::identity() };
+        }
+        let h = u2 - u1;
+        let i = h.double_ext() * h.double_ext();
+        let j = h * i;
+        let r = (s2 - s1).double_ext();
+        let v = u1 * i;
+        let x3 = r * r - j - v.double_ext();
+        let z = self.z + rhs.z;
+        Self { x: x3, y: r * (v - x3) - (s1 * j).double_ext(), z: (z * z - z1z1 - z2z2) * h }
+    }
+}
+
+impl<C: CurveParams> Neg for Point<C> {
+    type Output = Self;
+    fn neg(self) -> Self { Self { y: -self.y, ..self } }
+}
+
+impl<C: CurveParams> Sub for Point<C> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self { self + (-rhs) }
+}
+
+impl<C: CurveParams> PartialEq for Point<C> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.is_identity() || other.is_identity() { return self.is_identity() == other.is_identity(); }
+        let (z1z1, z2z2) = (self.z * self.z, other.z * other.z);
+        self.x * z2z2 == other.x * z1z1 && self.y * z2z2 * other.z == other.y * z1z1 * self.z
+    }
+}
+impl<C: CurveParams> Eq for Point<C> {}
+
+// sum of k_i·P_i
+pub fn msm<C: CurveParams>(points: &[Point<C>], scalars: &[Fr]) -> Point<C> {
+    assert_eq!(points.len(), scalars.len(), "one scalar per point");
+    points.iter().zip(scalars)
+        .filter(|(_, k)| !k.is_zero())
+        .fold(Point::identity(), |acc, (p, k)| acc + p.mul(k))
+}
+
+// ---- Ethereum encoding (EIP-196/197) ----
+// Big-endian 32-byte words; G1 = x || y, G2 = x.c1 || x.c0 || y.c1 || y.c0 (imaginary part
+// first), the point at infinity is all zeros.
+
+fn fq_to_be(x: &Fq) -> Vec<u8> { x.to_bytes_le().into_iter().rev().collect() }
+fn fq_from_be(bytes: &[u8]) -> Option<Fq> { Fq::from_bytes_le(&bytes.iter().rev().copied().collect::<Vec<_>>()) }
+
+impl G1 {
+    pub fn to_eth_bytes(&self) -> Vec<u8> {
+        match self.to_affine() {
+            Some((x, y)) => [fq_to_be(&x), fq_to_be(&y)].concat(),
+            None => vec![0; 64],
+        }
+    }
+    pub fn from_eth_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 64 { return None; }
+        if bytes.iter().all(|b| *b == 0) { return Some(Self::identity()); }
+        Self::from_affine(fq_from_be(&bytes[..32])?, fq_from_be(&bytes[32..])?)
+    }
+}
+
+impl G2 {
+    pub fn to_eth_bytes(&self) -> Vec<u8> {
+        match self.to_affine() {
+            Some((x, y)) => [fq_to_be(&x.c1), fq_to_be(&x.c0), fq_to_be(&y.c1), fq_to_be(&y.c0)].concat(),
+            None => vec![0; 128],
+        }
+    }
+    // also checks membership in the order-r subgroup
+    pub fn from_eth_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 128 { return None; }
+        if bytes.iter().all(|b| *b == 0) { return Some(Self::identity()); }
+        let w: Vec<Fq> = bytes.chunks(32).map(fq_from_be).collect::<Option<_>>()?;
+        let p = Self::from_affine(Fq2::new(w[1], w[0]), Fq2::new(w[3], w[2]))?;
+        p.is_in_subgroup().then_some(p)
+    }
+}
+
+// ---- Pairing ----
+// Reduced Tate pairing e(P, Q) = f_{r,P}(ψ(Q))^((p^12 - 1) / r), where ψ(x, y) = (x·w^2, y·w^3)
+// maps the twist into E(Fq12). The Miller loop runs over the bits of r with affine G1
+// arithmetic; vertical lines take values in Fq6 (ψ(Q) has its x-coordinate there) and are
+// dropped because the final exponentiation sends Fq6^* to 1. This is a different (but
+// equally bilinear and non-degenerate) pairing from the optimal ate pairing computed by the
+// EIP-197 precompile, so pairing-product equations agree while individual GT values do not.
+
+// (p^4 - p^2 + 1) / r
+const HARD_EXPONENT: [u64; 12] = [
+    0xe81bb482ccdf42b1, 0x5abf5cc4f49c36d4, 0xf1154e7e1da014fd, 0xdcc7b44c87cdbacf,
+    0xaaa441e3954bcf8a, 0x6b887d56d5095f23, 0x79581e16f3fd90c6, 0x3b1b1355d189227d,
+    0x4e529a5861876f6b, 0x6c0eb522d5b12278, 0x331ec15183177faf, 0x01baaa710b0759ad,
+];
+// p^2
+const P_SQUARED: [u64; 8] = [
+    0x3b5458a2275d69b1, 0xa602072d09eac101, 0x4a50189c6d96cadc, 0x04689e957a1242c8,
+    0x26edfa5c34c6b38d, 0xb00b855116375606, 0x599a6f7c0348d21c, 0x0925c4b8763cbf9c,
+];
+
+fn miller_loop(p: &G1, q: &G2) -> Fq12 {
+    let (Some((xp, yp)), Some((xq, yq))) = (p.to_affine(), q.to_affine()) else { return Fq12::ONE };
+    let xq = Fq12 { c0: Fq6 { c1: xq, ..Fq6::ZERO }, c1: Fq6::ZERO };
+    let yq = Fq12 { c0: Fq6::ZERO, c1: Fq6 { c1: yq, ..Fq6::ZERO } };
+    // line through (xt, yt) with slope λ, evaluated at ψ(Q): y_Q - λ·x_Q + (λ·x_t - y_t)
+    let line = |xt: Fq, yt: Fq, lambda: Fq| yq - xq.scale(lambda) + Fq12::from_fq(lambda * xt - yt);
+
+    let r = Fr::MODULUS;
+    let top = 64 * r.len() - r[r.len() - 1].leading_zeros() as usize - 1;
+    let (mut xt, mut yt) = (xp, yp);
+    let mut f = Fq12::ONE;
+    for i in (0..top).rev() {
+        // r is odd, so G1 has no 2-torsion and y_t != 0
+        let lambda = xt.square() * Fq::from_u64(3) * yt.double().inverse().unwrap();
+        f = f.square() * line(xt, yt, lambda);
+        let x2 = lambda.square() - xt.double();
+        (xt, yt) = (x2, lambda * (xt - x2) - yt);
+        if (r[i / 64] >> (i % 64)) & 1 == 1 {
+            // T = -P only in the last step, where T + P = O: the line is vertical
+            if xt == xp { continue; }
+            let lambda = (yt - yp) * (xt - xp).inverse().unwrap();
+            f *= line(xt, yt, lambda);
+            let x3 = lambda.square() - xt - xp;
+            (xt, yt) = (x3, lambda * (xt - x3) - yt);
+        }
+    }
+    f
+}
+
+fn final_exponentiation(f: Fq12) -> Fq12 {
+    // easy part: f^((p^6 - 1)(p^2 + 1)), with f^(p^6) the conjugate
+    let f1 = f.conjugate() * f.inverse().expect("Miller loop output is non-zero");
+    let f2 = f1.pow_limbs(&P_SQUARED) * f1;
+    f2.pow_limbs(&HARD_EXPONENT)
+}
+
+pub fn pairing(p: &G1, q: &G2) -> Fq12 { multi_pairing(&[(*p, *q)]) }
+
+// prod e(P_i, Q_i) with a single final exponentiation
+pub fn multi_pairing(pairs: &[(G1, G2)]) -> Fq12 {
+    final_exponentiation(pairs.iter().fold(Fq12::ONE, |acc, (p, q)| acc * miller_loop(p, q)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::PrimeField;
+
+    #[test]
+    fn fr_arithmetic() {
+        let minus_one = -Fr::ONE;
+        assert_eq!(minus_one * minus_one, Fr::ONE);
+        assert_eq!(minus_one.to_string(), "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000");
+        let x = Fr::from_u64(0xdead_beef);
+        assert_eq!(x * x.inverse().unwrap(), Fr::ONE);
+        assert_eq!(Fr::from_bytes_le(&x.to_bytes_le()), Some(x));
+        let w = Fr::two_adic_root_of_unity();
+        assert_eq!(w.pow(1 << 27), minus_one);
+    }
+
+    #[test]
+    fn tower_arithmetic() {
+        let a = Fq2::new(Fq::from_u64(5), -Fq::from_u64(7));
+        assert_eq!(a * a.inverse().unwrap(), Fq2::ONE);
+        // u^2 = -1
+        let u = Fq2::new(Fq::ZERO, Fq::ONE);
+        assert_eq!(u * u, -Fq2::ONE);
+        let b = Fq6 { c0: a, c1: Fq2::ONE, c2: a * a };
+        assert_eq!(b * b.inverse().unwrap(), Fq6::ONE);
+        // v^3 = ξ
+        let v = Fq6 { c1: Fq2::ONE, ..Fq6::ZERO };
+        assert_eq!(v * v * v, Fq6 { c0: Fq2::ONE.mul_by_nonresidue(), ..Fq6::ZERO });
+        let c = Fq12 { c0: b, c1: b.mul_by_v() + Fq6::ONE };
+        assert_eq!(c * c.inverse().unwrap(), Fq12::ONE);
+        // conjugation is the p^6-power Frobenius
+        let mut p6 = Fq::MODULUS.to_vec();
+        p6.resize(24, 0);
+        let p6 = (0..5).fold(p6.clone(), |acc, _| mul_limbs(&acc, Fq::MODULUS));
+        assert_eq!(c.pow_limbs(&p6), c.conjugate());
+    }
+
+    // schoolbook big-integer product, truncated to a's length
+    fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut out = vec![0u64; a.len()];
+        for (i, x) in a.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, y) in b.iter().enumerate() {
+                if i + j >= out.len() { break; }
+                let t = out[i + j] as u128 + *x as u128 * *y as u128 + carry;
+                out[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            if i + b.len() < out.len() { out[i + b.len()] = carry as u64; }
+        }
+        out
+    }
+
+    #[test]
+    fn groups() {
+        let (g1, g2) = (G1::generator(), G2::generator());
+        assert!(g1.is_on_curve() && g2.is_on_curve());
+        assert!(g1.is_in_subgroup() && g2.is_in_subgroup());
+        let (a, b) = (Fr::from_u64(123456789), -Fr::from_u64(42));
+        assert_eq!(g1.mul(&a) + g1.mul(&b), g1.mul(&(a + b)));
+        assert_eq!(g2.mul(&a).mul(&b), g2.mul(&(a * b)));
+        assert_eq!(g1.mul(&a) - g1.mul(&a), G1::identity());
+        assert_eq!(g2.double(), g2 + g2);
+        // 2G1 from the EIP-196 test vectors
+        assert_eq!(hex(&g1.double().to_eth_bytes()[..32]), "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3");
+        for p in [g1.mul(&a), G1::identity()] {
+            assert_eq!(G1::from_eth_bytes(&p.to_eth_bytes()), Some(p));
+        }
+        assert_eq!(G2::from_eth_bytes(&g2.mul(&a).to_eth_bytes()), Some(g2.mul(&a)));
+        assert_eq!(&hex(&g2.to_eth_bytes())[..64], "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2");
+    }
+
+    fn hex(b: &[u8]) -> String { b.iter().map(|x| format!("{x:02x}")).collect() }
+
+    #[test]
+    fn pairing_is_bilinear() {
+        let (g1, g2) = (G1::generator(), G2::generator());
+        let (a, b) = (Fr::from_u64(0xdead_beef), Fr::from_u64(1234567));
+        let e = pairing(&g1, &g2);
+        assert_ne!(e, Fq12::ONE);
+        assert_eq!(e.pow_limbs(Fr::MODULUS), Fq12::ONE);
+        let eab = pairing(&g1.mul(&a), &g2.mul(&b));
+        assert_eq!(eab, e.pow_limbs(&(a * b).to_limbs()));
+        assert_eq!(eab, pairing(&g1.mul(&(a * b)), &g2));
+        // e(aP, Q)·e(-P, aQ) = 1
+        assert_eq!(multi_pairing(&[(g1.mul(&a), g2), (-g1, g2.mul(&a))]), Fq12::ONE);
+        assert_eq!(pairing(&G1::identity(), &g2), Fq12::ONE);
+    }
+}
@@ -1,143 +1,561 @@
 // delta_zk.rs — lightweight constraint "builder" + verifier demo.
 // Goal: assemble tiny R1CS-like constraints and verify a witness over a prime field.
-// This is NOT a full zk system, but it's useful for prototyping circuits quickly.
+// The builder alone is not a zk system, but it's useful for prototyping circuits quickly;
+// groth16.rs (feature `bn254`) turns the same circuits into Groth16 proofs over BN254, and
+// spartan.rs proves them transparently with sumchecks and a pluggable commitment (pcs.rs);
+// nova.rs (feature `bn254`) folds iterated step circuits into one accumulator (its verifier
+// is still linear in the step count), and air.rs / stark.rs express the same kind of
+// iteration as an execution trace proven with FRI.
 //
 // Usage pattern (as a lib or bin scaffold):
-//   - Define variables (id => value) in the witness.
+//   - Allocate variables on the Builder; alloc() records the value into the builder's
+//     witness (Builder::witness / into_parts). A Builder::setup() builder drops values, so
+//     the same circuit code yields just the constraint system.
+//   - Variable 0 is reserved for the constant 1. alloc_public() / alloc_input() add an
+//     instance value (ordered, see Builder::public_inputs); alloc()/alloc_witness() add
+//     private witness.
 //   - Add constraints of the form:  (A·X) * (B·X) - (C·X) = 0 mod p
 //     where (A·X) = sum(A_i * x_i), similar for B,C.
-//   - Verify() checks all constraints modulo the field prime.
+//   - Verify() checks all constraints modulo the field prime. Every referenced variable must
+//     be assigned; verify_lenient() keeps the old "missing means zero" behavior.
 //
 // Includes a toy Poseidon-like round (very simplified S-box + MDS) for experimentation.
+// The full Poseidon permutation (round constants, full/partial rounds) is in poseidon.rs.
+//
+// Everything is generic over `F: PrimeField` (field.rs). Goldilocks (p = 2^64 - 2^32 + 1) is
+// always available; the BN254 and BLS12-381 scalar fields sit behind the `bn254` and
+// `bls12_381` cargo features.
 
 use std::collections::HashMap;
+use std::fmt;
+
+#[macro_use]
+mod field;
+mod goldilocks;
+#[cfg(feature = "bn254")]
+pub mod bn254;
+#[cfg(feature = "bls12_381")]
+pub mod bls12_381;
+pub mod poseidon;
+pub mod poseidon2;
+pub mod rescue;
+pub mod merkle;
+mod extension;
+pub mod ntt;
+pub mod poly;
+pub mod transcript;
+pub mod multilinear;
+pub mod pcs;
+pub mod spartan;
+pub mod fri;
+pub mod air;
+pub mod stark;
+#[cfg(feature = "bn254")]
+pub mod groth16;
+#[cfg(feature = "bn254")]
+pub mod nova;
+pub mod gadgets;
+mod keccak;
 
-const P: u128 = 0xffff_ffff_0000_0001; // 2^64-based BN-like toy prime (placeholder)
-type F = u128;
+pub use field::{batch_inverse, ExtensionField, PrimeField};
+pub use goldilocks::Goldilocks;
+pub use extension::{GoldilocksExt2, GoldilocksExt3};
 
 #[derive(Clone, Debug, Default)]
-pub struct LinComb {
+pub struct LinComb<F: PrimeField> {
     // linear combination: sum(coeff[i] * var[i]) + const_term
     pub terms: Vec<(usize, F)>,
     pub const_term: F,
 }
 
-impl LinComb {
-    pub fn new() -> Self { Self { terms: vec![], const_term: 0 } }
-    pub fn c(mut self, k: F) -> Self { self.const_term = add(self.const_term, k); self }
+impl<F: PrimeField> LinComb<F> {
+    pub fn new() -> Self { Self { terms: vec![], const_term: F::ZERO } }
+    pub fn c(mut self, k: F) -> Self { self.const_term += k; self }
     pub fn t(mut self, var: usize, coeff: F) -> Self { self.terms.push((var, coeff)); self }
-    pub fn eval(&self, w: &Witness) -> F {
-        let mut acc = self.const_term;
+    pub fn var(var: usize) -> Self { Self::new().t(var, F::ONE) }
+    pub fn constant(k: F) -> Self { Self::new().c(k) }
+    // self + k * other, with duplicate variables merged
+    pub fn add_scaled(mut self, other: &Self, k: F) -> Self {
+        self.const_term += k * other.const_term;
+        self.terms.extend(other.terms.iter().map(|(v, c)| (*v, k * *c)));
+        self.simplify()
+    }
+    // merge duplicate variables and drop zero coefficients (terms end up sorted by variable)
+    pub fn simplify(mut self) -> Self {
+        self.terms.sort_by_key(|(v, _)| *v);
+        let mut out: Vec<(usize, F)> = Vec::with_capacity(self.terms.len());
+        for (v, c) in self.terms {
+            match out.last_mut() {
+                Some((lv, lc)) if *lv == v => *lc += c,
+                _ => out.push((v, c)),
+            }
+        }
+        out.retain(|(_, c)| !c.is_zero());
+        self.terms = out;
+        self
+    }
+    // Missing witness entries count as zero; see `try_verify` for the strict check. The
+    // witness may live in an extension of F (coefficients are embedded with `from_base`).
+    pub fn eval<E: ExtensionField<F>>(&self, w: &Witness<E>) -> E {
+        let mut acc = E::from_base(self.const_term);
         for (v, c) in &self.terms {
-            let xv = *w.values.get(v).unwrap_or(&0);
-            acc = add(acc, mul(*c, xv));
+            let xv = w.values.get(v).copied().unwrap_or_default();
+            acc += E::from_base(*c) * xv;
         }
         acc
     }
 }
 
 #[derive(Clone, Debug)]
-pub struct Constraint {
+pub struct Constraint<F: PrimeField> {
     // (A·X) * (B·X) - (C·X) = 0  (mod P)
-    pub a: LinComb,
-    pub b: LinComb,
-    pub c: LinComb,
+    pub a: LinComb<F>,
+    pub b: LinComb<F>,
+    pub c: LinComb<F>,
+    // optional name shown by `verify_detailed` diagnostics
+    pub label: Option<String>,
 }
 
+impl<F: PrimeField> Constraint<F> {
+    // sorted, deduplicated indices of every variable referenced by A, B or C
+    pub fn vars(&self) -> Vec<usize> {
+        let mut vs: Vec<usize> = [&self.a, &self.b, &self.c]
+            .iter()
+            .flat_map(|lc| lc.terms.iter().map(|(v, _)| *v))
+            .collect();
+        vs.sort_unstable();
+        vs.dedup();
+        vs
+    }
+}
+
+// F is the builder's field or an extension of it
 #[derive(Clone, Debug, Default)]
-pub struct Witness {
+pub struct Witness<F> {
     // variable index -> value
     pub values: HashMap<usize, F>,
 }
 
-#[derive(Default)]
-pub struct Builder {
-    pub constraints: Vec<Constraint>,
+impl<F: PrimeField> Witness<F> {
+    // the same assignment viewed in an extension of F
+    pub fn lift<E: ExtensionField<F>>(&self) -> Witness<E> {
+        Witness { values: self.values.iter().map(|(v, x)| (*v, E::from_base(*x))).collect() }
+    }
+}
+
+pub struct Builder<F: PrimeField> {
+    pub constraints: Vec<Constraint<F>>,
     pub next_var: usize,
+    // instance variables, in the order verifiers supply them
+    pub public_inputs: Vec<usize>,
+    // variable index -> assigned value (None in setup mode, except for ONE)
+    pub values: Vec<Option<F>>,
+    // setup mode: only the constraint system is built, no assignment
+    pub setup: bool,
+}
+
+impl<F: PrimeField> Default for Builder<F> {
+    fn default() -> Self { Self::new() }
 }
 
-impl Builder {
-    pub fn new() -> Self { Self { constraints: vec![], next_var: 0 } }
-    pub fn alloc(&mut self, val: F) -> usize {
+impl<F: PrimeField> Builder<F> {
+    // reserved variable holding the constant 1 (known even in setup mode)
+    pub const ONE: usize = 0;
+
+    pub fn new() -> Self {
+        Self { constraints: vec![], next_var: 1, public_inputs: vec![], values: vec![Some(F::ONE)], setup: false }
+    }
+    pub fn setup() -> Self { Self { setup: true, ..Self::new() } }
+    pub fn alloc(&mut self, val: F) -> usize { self.alloc_opt(Some(val)) }
+    pub fn alloc_witness(&mut self, val: F) -> usize { self.alloc_opt(Some(val)) }
+    pub fn alloc_public(&mut self, val: F) -> usize { self.alloc_public_opt(Some(val)) }
+    pub fn alloc_input(&mut self, val: F) -> usize { self.alloc_public(val) }
+    pub fn alloc_public_opt(&mut self, val: Option<F>) -> usize {
+        let id = self.alloc_opt(val);
+        self.public_inputs.push(id);
+        id
+    }
+    pub fn is_public(&self, var: usize) -> bool { self.public_inputs.contains(&var) }
+    // instance vector in public_inputs order; None if any value is unknown (e.g. setup mode)
+    pub fn public_values(&self) -> Option<Vec<F>> {
+        self.public_inputs.iter().map(|&v| self.value(v)).collect()
+    }
+    // circuit code passes None when the value is unknown (e.g. derived from other values in setup mode)
+    pub fn alloc_opt(&mut self, val: Option<F>) -> usize {
         let id = self.next_var; self.next_var += 1;
+        self.values.push(if self.setup { None } else { val });
         id
     }
-    pub fn constrain(&mut self, a: LinComb, b: LinComb, c: LinComb) {
-        self.constraints.push(Constraint { a, b, c });
+    // assigned value of `var`; None in setup mode or if it was allocated without one
+    pub fn value(&self, var: usize) -> Option<F> { self.values.get(var).copied().flatten() }
+    pub fn witness(&self) -> Witness<F> {
+        let values = self.values.iter().enumerate().filter_map(|(i, v)| v.map(|v| (i, v))).collect();
+        Witness { values }
+    }
+    pub fn into_parts(self) -> (Vec<Constraint<F>>, Witness<F>) {
+        let w = self.witness();
+        (self.constraints, w)
+    }
+    // value of a linear combination under the current assignment (None if any input is unknown)
+    pub fn eval_lc(&self, lc: &LinComb<F>) -> Option<F> {
+        lc.terms.iter().try_fold(lc.const_term, |acc, (v, c)| Some(acc + *c * self.value(*v)?))
+    }
+    // allocate z = a * b and enforce it with one constraint
+    pub fn mul_lc(&mut self, a: &LinComb<F>, b: &LinComb<F>) -> usize {
+        let val = self.eval_lc(a).zip(self.eval_lc(b)).map(|(x, y)| x * y);
+        let z = self.alloc_opt(val);
+        self.constrain(a.clone(), b.clone(), LinComb::var(z));
+        z
+    }
+    // allocate z = x^e (e >= 1) by square-and-multiply, one constraint per step
+    pub fn pow_lc(&mut self, x: &LinComb<F>, e: u64) -> LinComb<F> {
+        assert!(e >= 1, "pow_lc needs e >= 1");
+        let mut acc = x.clone();
+        for i in (0..63 - e.leading_zeros()).rev() {
+            acc = LinComb::var(self.mul_lc(&acc, &acc));
+            if (e >> i) & 1 == 1 { acc = LinComb::var(self.mul_lc(&acc, x)); }
+        }
+        acc
+    }
+    // allocate a variable equal to `lc` (one constraint); returns lc's variable if it already is one
+    pub fn to_var(&mut self, lc: &LinComb<F>) -> usize {
+        if let ([(v, c)], true) = (lc.terms.as_slice(), lc.const_term.is_zero()) {
+            if *c == F::ONE { return *v; }
+        }
+        let val = self.eval_lc(lc);
+        let z = self.alloc_opt(val);
+        self.constrain(lc.clone(), LinComb::constant(F::ONE), LinComb::var(z));
+        z
+    }
+    pub fn constrain(&mut self, a: LinComb<F>, b: LinComb<F>, c: LinComb<F>) {
+        self.constraints.push(Constraint { a, b, c, label: None });
+    }
+    pub fn constrain_named(&mut self, label: impl Into<String>, a: LinComb<F>, b: LinComb<F>, c: LinComb<F>) {
+        self.constraints.push(Constraint { a, b, c, label: Some(label.into()) });
     }
     pub fn mul_gate(&mut self, x: usize, y: usize, z: usize) {
         // enforce: x * y - z = 0
-        let a = LinComb::new().t(x, 1);
-        let b = LinComb::new().t(y, 1);
-        let c = LinComb::new().t(z, 1);
+        let a = LinComb::new().t(x, F::ONE);
+        let b = LinComb::new().t(y, F::ONE);
+        let c = LinComb::new().t(z, F::ONE);
         self.constrain(a, b, c);
     }
     pub fn add_gate(&mut self, x: usize, y: usize, z: usize) {
         // enforce: (x + y) - z = 0  ==> (x + y) * 1 - z = 0
-        let a = LinComb::new().t(x, 1).t(y, 1);
-        let b = LinComb::new().c(1);
-        let c = LinComb::new().t(z, 1);
+        let a = LinComb::new().t(x, F::ONE).t(y, F::ONE);
+        let b = LinComb::new().c(F::ONE);
+        let c = LinComb::new().t(z, F::ONE);
         self.constrain(a, b, c);
     }
 }
 
-pub fn verify(builder: &Builder, wit: &Witness) -> bool {
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    // variables referenced by some constraint but missing from the witness (sorted)
+    Unassigned(Vec<usize>),
+    // index of the first unsatisfied constraint
+    Unsatisfied(usize),
+    // verify_with_public_inputs got the wrong number of instance values
+    PublicInputCount { expected: usize, got: usize },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Unassigned(vs) => write!(f, "unassigned witness variables: {vs:?}"),
+            VerifyError::Unsatisfied(i) => write!(f, "constraint #{i} is not satisfied"),
+            VerifyError::PublicInputCount { expected, got } => {
+                write!(f, "expected {expected} public inputs, got {got}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+// Strict verification: every variable a constraint references must be in the witness.
+// The witness may take values in an extension E of the builder's field.
+pub fn try_verify<F: PrimeField, E: ExtensionField<F>>(builder: &Builder<F>, wit: &Witness<E>) -> Result<(), VerifyError> {
+    let mut missing: Vec<usize> = builder.constraints.iter()
+        .flat_map(|con| con.vars())
+        .filter(|v| !wit.values.contains_key(v))
+        .collect();
+    if !missing.is_empty() {
+        missing.sort_unstable();
+        missing.dedup();
+        return Err(VerifyError::Unassigned(missing));
+    }
+    match builder.constraints.iter().position(|con| con.a.eval(wit) * con.b.eval(wit) != con.c.eval(wit)) {
+        Some(i) => Err(VerifyError::Unsatisfied(i)),
+        None => Ok(()),
+    }
+}
+
+pub fn verify<F: PrimeField, E: ExtensionField<F>>(builder: &Builder<F>, wit: &Witness<E>) -> bool {
+    try_verify(builder, wit).is_ok()
+}
+
+// Verifier-side entry point: the instance comes from `public` (in `builder.public_inputs`
+// order), the constant-one variable is fixed, and only the remaining variables are taken
+// from `private`.
+pub fn verify_with_public_inputs<F: PrimeField>(builder: &Builder<F>, public: &[F], private: &Witness<F>) -> Result<(), VerifyError> {
+    if public.len() != builder.public_inputs.len() {
+        return Err(VerifyError::PublicInputCount { expected: builder.public_inputs.len(), got: public.len() });
+    }
+    let mut values: HashMap<usize, F> = private.values.iter()
+        .filter(|(v, _)| **v != Builder::<F>::ONE && !builder.is_public(**v))
+        .map(|(v, x)| (*v, *x))
+        .collect();
+    values.insert(Builder::<F>::ONE, F::ONE);
+    values.extend(builder.public_inputs.iter().copied().zip(public.iter().copied()));
+    try_verify(builder, &Witness { values })
+}
+
+// Opt-in legacy mode: unassigned variables are treated as zero.
+pub fn verify_lenient<F: PrimeField, E: ExtensionField<F>>(builder: &Builder<F>, wit: &Witness<E>) -> bool {
     builder.constraints.iter().all(|con| {
         let a = con.a.eval(wit);
         let b = con.b.eval(wit);
         let c = con.c.eval(wit);
-        sub(mul(a, b), c) % P == 0
+        a * b == c
     })
 }
 
-// ---- Tiny field ops ----
-#[inline] fn add(a: F, b: F) -> F { let (s, o) = a.overflowing_add(b); (s as u128 + (o as u128)*0) % P }
-#[inline] fn sub(a: F, b: F) -> F { (a + P - (b % P)) % P }
-#[inline] fn mul(a: F, b: F) -> F {
-    // schoolbook 128-bit mul mod P (naive; fine for small prototypes)
-    let res = (a as u128).wrapping_mul(b as u128) % P;
-    res
+// ---- Diagnostics ----
+#[derive(Clone, Debug)]
+pub struct ConstraintFailure<F: PrimeField> {
+    pub index: usize,
+    pub label: Option<String>,
+    // evaluated A·X, B·X, C·X
+    pub a: F,
+    pub b: F,
+    pub c: F,
+    // every variable the constraint references, with its witness value (None if unassigned)
+    pub assignments: Vec<(usize, Option<F>)>,
+}
+
+// Like `verify`, but reports every unsatisfied constraint instead of stopping at "no".
+pub fn verify_detailed<F: PrimeField>(builder: &Builder<F>, wit: &Witness<F>) -> Vec<ConstraintFailure<F>> {
+    builder.constraints.iter().enumerate().filter_map(|(index, con)| {
+        let (a, b, c) = (con.a.eval(wit), con.b.eval(wit), con.c.eval(wit));
+        if a * b == c { return None; }
+        let assignments = con.vars().into_iter().map(|v| (v, wit.values.get(&v).copied())).collect();
+        Some(ConstraintFailure { index, label: con.label.clone(), a, b, c, assignments })
+    }).collect()
+}
+
+impl<F: PrimeField> fmt::Display for ConstraintFailure<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "constraint #{}", self.index)?;
+        if let Some(l) = &self.label { write!(f, " ({l})")?; }
+        writeln!(f, ": (A·X) * (B·X) != (C·X)")?;
+        writeln!(f, "    A·X = {}", self.a)?;
+        writeln!(f, "    B·X = {}", self.b)?;
+        writeln!(f, "    A·X * B·X = {}", self.a * self.b)?;
+        write!(f, "    C·X = {}", self.c)?;
+        for (v, val) in &self.assignments {
+            match val {
+                Some(x) => write!(f, "\n    x{v} = {x}")?,
+                None => write!(f, "\n    x{v} = <unassigned>")?,
+            }
+        }
+        Ok(())
+    }
 }
-#[inline] fn exp(mut x: F, mut e: u128) -> F {
-    let mut r: F = 1;
-    while e > 0 {
-        if e & 1 == 1 { r = mul(r, x); }
-        x = mul(x, x); e >>= 1;
+
+// Pretty-print a failure list for test output / panics.
+pub fn format_failures<F: PrimeField>(failures: &[ConstraintFailure<F>]) -> String {
+    if failures.is_empty() { return "all constraints satisfied".to_string(); }
+    let mut out = format!("{} constraint(s) failed:", failures.len());
+    for fail in failures {
+        out.push_str(&format!("\n{fail}"));
     }
-    r
+    out
 }
 
 // ---- Super-simplified Poseidon-ish round for experimentation ----
-pub fn poseidon_round(state: &mut [F; 3]) {
+pub fn poseidon_round<F: PrimeField>(state: &mut [F; 3]) {
     // S-box: x^5
     for x in state.iter_mut() {
-        *x = exp(*x, 5);
+        *x = x.pow(5);
     }
     // MDS (toy 3x3)
-    let m = [[2u128, 1, 1],
+    let m = [[2u64, 1, 1],
              [1, 2, 1],
-             [1, 1, 2]];
-    let s0 = add(add(mul(m[0][0], state[0]), mul(m[0][1], state[1])), mul(m[0][2], state[2]));
-    let s1 = add(add(mul(m[1][0], state[0]), mul(m[1][1], state[1])), mul(m[1][2], state[2]));
-    let s2 = add(add(mul(m[2][0], state[0]), mul(m[2][1], state[1])), mul(m[2][2], state[2]));
+             [1, 1, 2]].map(|row| row.map(F::from_u64));
+    let s0 = m[0][0] * state[0] + m[0][1] * state[1] + m[0][2] * state[2];
+    let s1 = m[1][0] * state[0] + m[1][1] * state[1] + m[1][2] * state[2];
+    let s2 = m[2][0] * state[0] + m[2][1] * state[1] + m[2][2] * state[2];
     state[0] = s0; state[1] = s1; state[2] = s2;
 }
 
+impl<F: PrimeField> Builder<F> {
+    // in-circuit poseidon_round: x^5 costs 3 constraints per cell, the MDS is folded into LinCombs
+    pub fn poseidon_round_gadget(&mut self, state: &[LinComb<F>; 3]) -> [LinComb<F>; 3] {
+        let sboxed = state.clone().map(|x| self.pow_lc(&x, 5));
+        let (one, two) = (F::ONE, F::from_u64(2));
+        [[two, one, one], [one, two, one], [one, one, two]].map(|row| {
+            row.iter().zip(&sboxed).fold(LinComb::new(), |acc, (m, x)| acc.add_scaled(x, *m))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
     fn add_and_mul_gate() {
+        let f = Goldilocks::from_u64;
         let mut b = Builder::new();
-        let x = b.alloc(3);
-        let y = b.alloc(5);
-        let z = b.alloc(15);
+        let x = b.alloc(f(3));
+        let y = b.alloc(f(5));
+        let z = b.alloc(f(15));
         b.mul_gate(x, y, z);
 
         let mut w = Witness::default();
-        w.values.insert(x, 3);
-        w.values.insert(y, 5);
-        w.values.insert(z, 15);
+        w.values.insert(x, f(3));
+        w.values.insert(y, f(5));
+        w.values.insert(z, f(15));
+        assert!(verify(&b, &w));
+    }
+
+    #[test]
+    fn builder_records_witness() {
+        // x * y = z, z + x = out; the same code runs in setup mode without values
+        fn circuit(b: &mut Builder<Goldilocks>, x: Option<Goldilocks>, y: Option<Goldilocks>) -> usize {
+            let (xv, yv) = (b.alloc_opt(x), b.alloc_opt(y));
+            let z = b.alloc_opt(b.value(xv).zip(b.value(yv)).map(|(x, y)| x * y));
+            let out = b.alloc_opt(b.value(z).zip(b.value(xv)).map(|(z, x)| z + x));
+            b.mul_gate(xv, yv, z);
+            b.add_gate(z, xv, out);
+            out
+        }
+        let f = Goldilocks::from_u64;
+        let mut b = Builder::new();
+        let out = circuit(&mut b, Some(f(3)), Some(f(5)));
+        assert_eq!(b.value(out), Some(f(18)));
+        assert!(verify(&b, &b.witness()));
+
+        let mut s = Builder::setup();
+        circuit(&mut s, None, None);
+        assert_eq!(s.constraints.len(), b.constraints.len());
+        assert_eq!(s.witness().values.keys().collect::<Vec<_>>(), vec![&Builder::<Goldilocks>::ONE]);
+
+        let (constraints, w) = b.into_parts();
+        assert_eq!((constraints.len(), w.values.len()), (2, 5));
+    }
+
+    #[test]
+    fn public_inputs_are_supplied_by_verifier() {
+        // public out = x * x + 1, with x private
+        let f = Goldilocks::from_u64;
+        let mut b = Builder::new();
+        let out = b.alloc_public(f(10));
+        let x = b.alloc_witness(f(3));
+        let sq = b.alloc(f(9));
+        b.mul_gate(x, x, sq);
+        b.constrain(LinComb::new().t(sq, f(1)).t(Builder::<Goldilocks>::ONE, f(1)), LinComb::new().c(f(1)), LinComb::new().t(out, f(1)));
+        assert_eq!(b.public_inputs, vec![out]);
+        assert_eq!(b.public_values(), Some(vec![f(10)]));
+
+        let private = b.witness();
+        assert_eq!(verify_with_public_inputs(&b, &[f(10)], &private), Ok(()));
+        // the private witness cannot override the instance
+        assert_eq!(verify_with_public_inputs(&b, &[f(11)], &private), Err(VerifyError::Unsatisfied(1)));
+        assert_eq!(verify_with_public_inputs(&b, &[], &private), Err(VerifyError::PublicInputCount { expected: 1, got: 0 }));
+
+        // alloc_input is the same as alloc_public
+        let mut c = Builder::new();
+        let (i, p) = (c.alloc_input(f(4)), c.alloc_public(f(5)));
+        assert_eq!((c.public_inputs.clone(), c.public_values()), (vec![i, p], Some(vec![f(4), f(5)])));
+    }
+
+    #[test]
+    fn poseidon_round_gadget_matches_native() {
+        let f = Goldilocks::from_u64;
+        let mut native = [f(1), f(2), f(3)];
+        let mut b = Builder::new();
+        let mut state = native.map(|x| LinComb::var(b.alloc(x)));
+        for _ in 0..4 {
+            poseidon_round(&mut native);
+            state = b.poseidon_round_gadget(&state);
+        }
+        assert_eq!(b.constraints.len(), 4 * 3 * 3);
+        assert_eq!(state.map(|lc| b.eval_lc(&lc).unwrap()), native);
+        assert!(verify(&b, &b.witness()));
+    }
+
+    #[test]
+    fn mul_gate_near_modulus() {
+        // (p-1) * (p-2) = 2 mod p; the product needs the full 128-bit reduction
+        let f = Goldilocks::from_u64;
+        let mut b = Builder::new();
+        let (x, y, z) = (b.alloc(f(0)), b.alloc(f(0)), b.alloc(f(0)));
+        b.mul_gate(x, y, z);
+        let mut w = Witness::default();
+        w.values.insert(x, f(Goldilocks::ORDER - 1));
+        w.values.insert(y, f(Goldilocks::ORDER - 2));
+        w.values.insert(z, f(2));
+        assert!(verify(&b, &w));
+    }
+
+    #[test]
+    fn verify_detailed_reports_failures() {
+        let f = Goldilocks::from_u64;
+        let mut b = Builder::new();
+        let (x, y, z, u) = (b.alloc(f(0)), b.alloc(f(0)), b.alloc(f(0)), b.alloc(f(0)));
+        b.mul_gate(x, y, z);
+        b.constrain_named("sum", LinComb::new().t(x, f(1)).t(u, f(1)), LinComb::new().c(f(1)), LinComb::new().t(z, f(1)));
+        let mut w = Witness::default();
+        w.values.insert(x, f(2));
+        w.values.insert(y, f(3));
+        w.values.insert(z, f(7));
+
+        let fails = verify_detailed(&b, &w);
+        assert_eq!(fails.len(), 2);
+        assert_eq!((fails[0].index, fails[0].a, fails[0].b, fails[0].c), (0, f(2), f(3), f(7)));
+        assert_eq!(fails[1].label.as_deref(), Some("sum"));
+        assert_eq!(fails[1].assignments, vec![(x, Some(f(2))), (z, Some(f(7))), (u, None)]);
+        let report = format_failures(&fails);
+        assert!(report.contains("constraint #1 (sum)") && report.contains(&format!("x{u} = <unassigned>")), "{report}");
+
+        w.values.insert(z, f(6));
+        w.values.insert(u, f(4));
+        assert!(verify_detailed(&b, &w).is_empty());
+    }
+
+    #[test]
+    fn unassigned_variables_are_rejected() {
+        let f = Goldilocks::from_u64;
+        let mut b = Builder::new();
+        let (x, y, z) = (b.alloc(f(0)), b.alloc(f(0)), b.alloc(f(0)));
+        b.mul_gate(x, y, z);
+        b.add_gate(y, z, x);
+        // x = 0 makes x * y = z hold if y, z silently default to zero
+        let mut w = Witness::default();
+        w.values.insert(x, f(0));
+        assert_eq!(try_verify(&b, &w), Err(VerifyError::Unassigned(vec![y, z])));
+        assert!(!verify(&b, &w));
+        assert!(verify_lenient(&b, &w));
+
+        w.values.insert(y, f(1));
+        w.values.insert(z, f(0));
+        assert_eq!(try_verify(&b, &w), Err(VerifyError::Unsatisfied(1)));
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn mul_gate_over_bn254() {
+        let f = bn254::Fr::from_u64;
+        let mut b = Builder::new();
+        let (x, y, z) = (b.alloc(f(0)), b.alloc(f(0)), b.alloc(f(0)));
+        b.mul_gate(x, y, z);
+        let mut w = Witness::default();
+        w.values.insert(x, -f(3));
+        w.values.insert(y, f(5));
+        w.values.insert(z, -f(15));
         assert!(verify(&b, &w));
+        w.values.insert(z, f(15));
+        assert!(!verify(&b, &w));
     }
 }
@@ -0,0 +1,227 @@
+// extension.rs — quadratic and cubic extensions of Goldilocks for challenge sampling.
+//
+//   GoldilocksExt2 = F_p[X] / (X^2 - 7)
+//   GoldilocksExt3 = F_p[X] / (X^3 - 7)
+//
+// 7 generates F_p^*, so it is neither a square nor a cube (2 | p - 1 and 3 | p - 1), which
+// makes both polynomials irreducible. The Frobenius map x -> x^p fixes F_p and sends X to
+// 7^((p-1)/2)·X = -X in Ext2 and to 7^((p-1)/3)·X = γX in Ext3; inverses use the norm
+// N(a) = a·φ(a)·..·φ^(d-1)(a), which lies in F_p.
+
+use crate::field::ExtensionField;
+use crate::Goldilocks;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+const W: Goldilocks = Goldilocks::from_u64(7);
+// 7^((p-1)/3), a primitive cube root of unity
+const GAMMA: Goldilocks = Goldilocks::from_u64(0xffff_fffe_0000_0001);
+
+// component-wise ops, multiplication by a base element, pow and Display shared by both types
+macro_rules! extension_ops {
+    ($name:ident, $d:expr) => {
+        impl $name {
+            pub const ZERO: Self = Self([Goldilocks::ZERO; $d]);
+            pub const ONE: Self = {
+                let mut c = [Goldilocks::ZERO; $d];
+                c[0] = Goldilocks::ONE;
+                Self(c)
+            };
+            pub const fn new(coeffs: [Goldilocks; $d]) -> Self { Self(coeffs) }
+            pub fn coeffs(&self) -> [Goldilocks; $d] { self.0 }
+            pub fn from_base(x: Goldilocks) -> Self {
+                let mut c = [Goldilocks::ZERO; $d];
+                c[0] = x;
+                Self(c)
+            }
+            pub fn is_zero(&self) -> bool { *self == Self::ZERO }
+            pub fn square(&self) -> Self { *self * *self }
+            pub fn pow(&self, mut e: u64) -> Self {
+                let (mut x, mut r) = (*self, Self::ONE);
+                while e > 0 {
+                    if e & 1 == 1 { r *= x; }
+                    x = x.square(); e >>= 1;
+                }
+                r
+            }
+            pub fn repeated_frobenius(&self, k: usize) -> Self {
+                (0..k % $d).fold(*self, |x, _| x.frobenius())
+            }
+            // a^(-1) = φ(a)·..·φ^(d-1)(a) / N(a)
+            pub fn inverse(&self) -> Option<Self> {
+                let conj = (1..$d).fold(Self::ONE, |acc, k| acc * self.repeated_frobenius(k));
+                let norm = (*self * conj).0[0];
+                norm.inverse().map(|n| conj * n)
+            }
+        }
+
+        impl ExtensionField<Goldilocks> for $name {
+            const DEGREE: usize = $d;
+            fn from_base(x: Goldilocks) -> Self { $name::from_base(x) }
+            fn invert(&self) -> Option<Self> { self.inverse() }
+            fn to_base_coeffs(&self) -> Vec<Goldilocks> { self.0.to_vec() }
+            fn from_base_coeffs(coeffs: &[Goldilocks]) -> Self {
+                Self(coeffs.try_into().expect("wrong number of coordinates"))
+            }
+        }
+
+        impl From<Goldilocks> for $name {
+            fn from(x: Goldilocks) -> Self { Self::from_base(x) }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self { Self(std::array::from_fn(|i| self.0[i] + rhs.0[i])) }
+        }
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self { Self(std::array::from_fn(|i| self.0[i] - rhs.0[i])) }
+        }
+        impl Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self { Self(self.0.map(|x| -x)) }
+        }
+        impl Mul<Goldilocks> for $name {
+            type Output = Self;
+            fn mul(self, rhs: Goldilocks) -> Self { Self(self.0.map(|x| x * rhs)) }
+        }
+        impl AddAssign for $name { fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; } }
+        impl SubAssign for $name { fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs; } }
+        impl MulAssign for $name { fn mul_assign(&mut self, rhs: Self) { *self = *self * rhs; } }
+
+        // a0 + a1·X + ..
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0[0])?;
+                for (i, c) in self.0.iter().enumerate().skip(1) {
+                    write!(f, " + {c}·X")?;
+                    if i > 1 { write!(f, "^{i}")?; }
+                }
+                Ok(())
+            }
+        }
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { fmt::Display::fmt(self, f) }
+        }
+    };
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct GoldilocksExt2(pub [Goldilocks; 2]);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct GoldilocksExt3(pub [Goldilocks; 3]);
+
+extension_ops!(GoldilocksExt2, 2);
+extension_ops!(GoldilocksExt3, 3);
+
+impl GoldilocksExt2 {
+    // a0 + a1·X -> a0 - a1·X
+    pub fn frobenius(&self) -> Self { Self([self.0[0], -self.0[1]]) }
+}
+
+impl GoldilocksExt3 {
+    // a0 + a1·X + a2·X^2 -> a0 + γ·a1·X + γ^2·a2·X^2
+    pub fn frobenius(&self) -> Self { Self([self.0[0], GAMMA * self.0[1], GAMMA.square() * self.0[2]]) }
+}
+
+impl Mul for GoldilocksExt2 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let ([a0, a1], [b0, b1]) = (self.0, rhs.0);
+        Self([a0 * b0 + W * a1 * b1, a0 * b1 + a1 * b0])
+    }
+}
+
+impl Mul for GoldilocksExt3 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let ([a0, a1, a2], [b0, b1, b2]) = (self.0, rhs.0);
+        Self([
+            a0 * b0 + W * (a1 * b2 + a2 * b1),
+            a0 * b1 + a1 * b0 + W * a2 * b2,
+            a0 * b2 + a1 * b1 + a2 * b0,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::goldilocks::P;
+    use crate::{verify, Builder, LinComb, Witness};
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+
+    #[test]
+    fn non_residue_constants() {
+        assert_eq!(W.pow((P - 1) / 2), -Goldilocks::ONE);
+        assert_eq!(W.pow((P - 1) / 3), GAMMA);
+        assert_ne!(GAMMA, Goldilocks::ONE);
+    }
+
+    #[test]
+    fn ext2_arithmetic() {
+        let a = GoldilocksExt2::new([f(3), f(5)]);
+        let b = GoldilocksExt2::new([-f(11), f(1 << 40)]);
+        assert_eq!(a * b, b * a);
+        assert_eq!((a + b) * a, a * a + b * a);
+        assert_eq!(a * a.inverse().unwrap(), GoldilocksExt2::ONE);
+        assert_eq!(GoldilocksExt2::ZERO.inverse(), None);
+        let x = GoldilocksExt2::new([f(0), f(1)]);
+        assert_eq!(x.square(), GoldilocksExt2::from_base(W));
+        // φ(a) = a^p, φ is a ring homomorphism of order 2 fixing F_p
+        assert_eq!(a.frobenius(), a.pow(P));
+        assert_eq!((a * b).frobenius(), a.frobenius() * b.frobenius());
+        assert_eq!(a.repeated_frobenius(2), a);
+        assert_eq!(GoldilocksExt2::from_base(f(9)).frobenius(), GoldilocksExt2::from_base(f(9)));
+    }
+
+    #[test]
+    fn ext3_arithmetic() {
+        let a = GoldilocksExt3::new([f(3), -f(5), f(7)]);
+        let b = GoldilocksExt3::new([f(2), f(1 << 50), f(0)]);
+        assert_eq!(a * b, b * a);
+        assert_eq!((a - b) * a, a * a - b * a);
+        assert_eq!(a * a.inverse().unwrap(), GoldilocksExt3::ONE);
+        assert_eq!(b * b.inverse().unwrap(), GoldilocksExt3::ONE);
+        let x = GoldilocksExt3::new([f(0), f(1), f(0)]);
+        assert_eq!(x.pow(3), GoldilocksExt3::from_base(W));
+        assert_eq!(a.frobenius(), a.pow(P));
+        assert_eq!(a.repeated_frobenius(2), a.pow(P).pow(P));
+        assert_eq!(a.repeated_frobenius(3), a);
+        assert_eq!(<GoldilocksExt3 as ExtensionField<Goldilocks>>::from_base_coeffs(&a.to_base_coeffs()), a);
+    }
+
+    #[test]
+    fn verify_over_extension() {
+        // x * y = z, x + y = s
+        let mut b = Builder::new();
+        let (x, y) = (b.alloc(f(6)), b.alloc(f(7)));
+        let z = b.alloc(f(42));
+        let s = b.alloc(f(13));
+        b.mul_gate(x, y, z);
+        b.add_gate(x, y, s);
+        let base = b.witness();
+        assert!(verify(&b, &base.lift::<GoldilocksExt3>()));
+
+        // a genuinely extension-valued solution: x = X, y = X, z = X^2, s = 2X
+        let xe = GoldilocksExt2::new([f(0), f(1)]);
+        let mut w: Witness<GoldilocksExt2> = base.lift();
+        w.values.insert(x, xe);
+        w.values.insert(y, xe);
+        w.values.insert(z, xe.square());
+        w.values.insert(s, xe + xe);
+        assert!(verify(&b, &w));
+        assert_eq!(LinComb::var(z).c(f(1)).eval(&w), GoldilocksExt2::new([f(8), f(0)]));
+
+        // random linear combination of the residuals with an extension challenge
+        w.values.insert(s, xe);
+        let r = GoldilocksExt2::new([f(0x1234), f(0x5678)]);
+        let rlc = b.constraints.iter().rev().fold(GoldilocksExt2::ZERO, |acc, con| {
+            acc * r + (con.a.eval(&w) * con.b.eval(&w) - con.c.eval(&w))
+        });
+        assert!(!rlc.is_zero());
+        assert!(!verify(&b, &w));
+    }
+}
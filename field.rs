@@ -0,0 +1,439 @@
+// field.rs — the `PrimeField` abstraction the builder, verifier and gadgets are generic over,
+// plus a Montgomery-form implementation for 256-bit prime fields (BN254 / BLS12-381 scalars).
+
+// the Montgomery machinery is only instantiated by the feature-gated curve fields
+#![cfg_attr(not(any(feature = "bn254", feature = "bls12_381")), allow(dead_code, unused_macros))]
+
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+pub trait PrimeField:
+    Copy + Default + Eq + Hash + Debug + Display + Send + Sync + 'static
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self>
+    + AddAssign + SubAssign + MulAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+    // p as little-endian u64 limbs
+    const MODULUS: &'static [u64];
+    const MODULUS_BITS: u32;
+    // largest s with 2^s | p - 1
+    const TWO_ADICITY: u32;
+    // length of the canonical byte encoding
+    const NUM_BYTES: usize;
+
+    fn from_u64(x: u64) -> Self;
+    // None for zero
+    fn inverse(&self) -> Option<Self>;
+    // generator of the full multiplicative group F_p^*
+    fn multiplicative_generator() -> Self;
+    // canonical little-endian encoding, NUM_BYTES long
+    fn to_bytes_le(&self) -> Vec<u8>;
+    // None unless exactly NUM_BYTES long and canonical (< p)
+    fn from_bytes_le(bytes: &[u8]) -> Option<Self>;
+
+    fn is_zero(&self) -> bool { *self == Self::ZERO }
+    fn double(&self) -> Self { *self + *self }
+    fn square(&self) -> Self { *self * *self }
+    fn pow(&self, e: u64) -> Self { self.pow_limbs(&[e]) }
+    // exponent as little-endian u64 limbs
+    fn pow_limbs(&self, e: &[u64]) -> Self {
+        let mut r = Self::ONE;
+        for limb in e.iter().rev() {
+            for i in (0..64).rev() {
+                r = r.square();
+                if (limb >> i) & 1 == 1 { r *= *self; }
+            }
+        }
+        r
+    }
+    // 1/x via Fermat: x^(p-2)
+    fn inverse_fermat(&self) -> Option<Self> {
+        if self.is_zero() { return None; }
+        let mut e = Self::MODULUS.to_vec();
+        e[0] -= 2; // p is odd and > 2
+        Some(self.pow_limbs(&e))
+    }
+    // 1/x via the binary extended Euclidean algorithm on (x, p), keeping the Bezout
+    // coefficients as field elements: x1·x ≡ u and x2·x ≡ v (mod p) throughout.
+    fn inverse_euclid(&self) -> Option<Self> {
+        if self.is_zero() { return None; }
+        let mut u = to_limbs(self);
+        let mut v = Self::MODULUS.to_vec();
+        let (mut x1, mut x2) = (Self::ONE, Self::ZERO);
+        // (p + 1) / 2 = 1/2
+        let half = limbs_to_field::<Self>(&shr_limbs(Self::MODULUS, 1)) + Self::ONE;
+        while !is_one(&u) && !is_one(&v) {
+            while u[0] & 1 == 0 { u = shr_limbs(&u, 1); x1 *= half; }
+            while v[0] & 1 == 0 { v = shr_limbs(&v, 1); x2 *= half; }
+            if limbs_ge(&u, &v) { sub_assign_limbs(&mut u, &v); x1 -= x2; }
+            else { sub_assign_limbs(&mut v, &u); x2 -= x1; }
+        }
+        Some(if is_one(&u) { x1 } else { x2 })
+    }
+    // x^((p-1)/2): 1 for non-zero squares, -1 for non-squares, 0 for zero
+    fn legendre(&self) -> i8 {
+        let s = self.pow_limbs(&shr_limbs(Self::MODULUS, 1));
+        if s.is_zero() { 0 } else if s == Self::ONE { 1 } else { -1 }
+    }
+    // Tonelli–Shanks with p - 1 = q·2^s; the multiplicative generator is the non-residue.
+    // Returns either root; None for non-squares.
+    fn sqrt(&self) -> Option<Self> {
+        match self.legendre() {
+            0 => return Some(Self::ZERO),
+            -1 => return None,
+            _ => {}
+        }
+        let q = shr_limbs(Self::MODULUS, Self::TWO_ADICITY); // odd, so (q - 1)/2 = q >> 1
+        let w = self.pow_limbs(&shr_limbs(&q, 1));
+        let mut x = *self * w; // a^((q+1)/2)
+        let mut t = x * w; // a^q
+        let mut c = Self::multiplicative_generator().pow_limbs(&q);
+        let mut m = Self::TWO_ADICITY;
+        while t != Self::ONE {
+            // least i with t^(2^i) = 1
+            let mut i = 0;
+            let mut t2 = t;
+            while t2 != Self::ONE { t2 = t2.square(); i += 1; }
+            let mut b = c;
+            for _ in 0..m - i - 1 { b = b.square(); }
+            x *= b;
+            c = b.square();
+            t *= c;
+            m = i;
+        }
+        Some(x)
+    }
+    // primitive 2^TWO_ADICITY-th root of unity: g^((p-1) / 2^s)
+    fn two_adic_root_of_unity() -> Self {
+        let mut e = Self::MODULUS.to_vec();
+        e[0] -= 1; // p is odd
+        Self::multiplicative_generator().pow_limbs(&shr_limbs(&e, Self::TWO_ADICITY))
+    }
+}
+
+// A field containing F, used for witnesses and challenges that live in an extension.
+// Every prime field is (trivially) an extension of itself.
+pub trait ExtensionField<F: PrimeField>:
+    Copy + Default + Eq + Debug + Display + Send + Sync + 'static
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self>
+    + AddAssign + SubAssign + MulAssign
+{
+    const DEGREE: usize;
+    fn from_base(x: F) -> Self;
+    // None for zero (named apart from `PrimeField::inverse` so both traits can be in scope)
+    fn invert(&self) -> Option<Self>;
+    // self^e (named apart from `PrimeField::pow` for the same reason)
+    fn exp(&self, mut e: u64) -> Self {
+        let (mut x, mut r) = (*self, Self::from_base(F::ONE));
+        while e > 0 {
+            if e & 1 == 1 { r *= x; }
+            x *= x;
+            e >>= 1;
+        }
+        r
+    }
+    // the DEGREE coordinates over F, for hashing and transcripts
+    fn to_base_coeffs(&self) -> Vec<F>;
+    // inverse of to_base_coeffs; panics unless coeffs.len() == DEGREE
+    fn from_base_coeffs(coeffs: &[F]) -> Self;
+}
+
+impl<F: PrimeField> ExtensionField<F> for F {
+    const DEGREE: usize = 1;
+    fn from_base(x: F) -> Self { x }
+    fn invert(&self) -> Option<Self> { self.inverse() }
+    fn to_base_coeffs(&self) -> Vec<F> { vec![*self] }
+    fn from_base_coeffs(coeffs: &[F]) -> Self {
+        assert_eq!(coeffs.len(), 1, "expected one coordinate");
+        coeffs[0]
+    }
+}
+
+// Montgomery's trick: invert every non-zero element of `xs` in place with one inversion
+// and 3(n - 1) multiplications; zeros are left as zero.
+pub fn batch_inverse<F: PrimeField>(xs: &mut [F]) {
+    let mut prefix = Vec::with_capacity(xs.len());
+    let mut acc = F::ONE;
+    for x in xs.iter() {
+        prefix.push(acc);
+        if !x.is_zero() { acc *= *x; }
+    }
+    let mut inv = acc.inverse().expect("product of non-zero elements");
+    for (x, p) in xs.iter_mut().zip(prefix).rev() {
+        if x.is_zero() { continue; }
+        let xi = inv * p;
+        inv *= *x;
+        *x = xi;
+    }
+}
+
+// ---- variable-length little-endian limb helpers ----
+
+// canonical representative as NUM_BYTES / 8 limbs
+pub(crate) fn to_limbs<F: PrimeField>(x: &F) -> Vec<u64> {
+    x.to_bytes_le().chunks(8).map(|c| {
+        let mut l = [0u8; 8];
+        l[..c.len()].copy_from_slice(c);
+        u64::from_le_bytes(l)
+    }).collect()
+}
+
+pub(crate) fn limbs_to_field<F: PrimeField>(limbs: &[u64]) -> F {
+    let two_64 = F::from_u64(1 << 63).double();
+    limbs.iter().rev().fold(F::ZERO, |acc, l| acc * two_64 + F::from_u64(*l))
+}
+
+pub(crate) fn limbs_ge(a: &[u64], b: &[u64]) -> bool {
+    for i in (0..a.len().max(b.len())).rev() {
+        let (x, y) = (a.get(i).copied().unwrap_or(0), b.get(i).copied().unwrap_or(0));
+        if x != y { return x > y; }
+    }
+    true
+}
+
+fn is_one(x: &[u64]) -> bool { x[0] == 1 && x[1..].iter().all(|l| *l == 0) }
+
+// a -= b, requires a >= b and a.len() >= b.len()
+fn sub_assign_limbs(a: &mut [u64], b: &[u64]) {
+    let mut borrow = false;
+    for (i, ai) in a.iter_mut().enumerate() {
+        let (d, b1) = ai.overflowing_sub(b.get(i).copied().unwrap_or(0));
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        *ai = d; borrow = b1 | b2;
+    }
+}
+
+pub(crate) fn shr_limbs(x: &[u64], s: u32) -> Vec<u64> {
+    let (words, bits) = ((s / 64) as usize, s % 64);
+    (0..x.len())
+        .map(|i| {
+            let lo = x.get(i + words).copied().unwrap_or(0);
+            let hi = x.get(i + words + 1).copied().unwrap_or(0);
+            if bits == 0 { lo } else { (lo >> bits) | (hi << (64 - bits)) }
+        })
+        .collect()
+}
+
+// ---- N-limb Montgomery helpers (p < 2^(64N - 1)) ----
+
+#[inline]
+pub(crate) fn geq<const N: usize>(a: &[u64; N], b: &[u64; N]) -> bool {
+    for i in (0..N).rev() {
+        if a[i] != b[i] { return a[i] > b[i]; }
+    }
+    true
+}
+
+#[inline]
+pub(crate) fn sub_limbs<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], bool) {
+    let mut r = [0u64; N];
+    let mut borrow = false;
+    for i in 0..N {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        r[i] = d; borrow = b1 | b2;
+    }
+    (r, borrow)
+}
+
+#[inline]
+pub(crate) fn add_mod<const N: usize>(a: &[u64; N], b: &[u64; N], p: &[u64; N]) -> [u64; N] {
+    let mut r = [0u64; N];
+    let mut carry = false;
+    for i in 0..N {
+        let (s, c1) = a[i].overflowing_add(b[i]);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        r[i] = s; carry = c1 | c2;
+    }
+    if carry || geq(&r, p) { sub_limbs(&r, p).0 } else { r }
+}
+
+#[inline]
+pub(crate) fn sub_mod<const N: usize>(a: &[u64; N], b: &[u64; N], p: &[u64; N]) -> [u64; N] {
+    let (r, borrow) = sub_limbs(a, b);
+    if !borrow { return r; }
+    let mut out = [0u64; N];
+    let mut carry = false;
+    for i in 0..N {
+        let (s, c1) = r[i].overflowing_add(p[i]);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        out[i] = s; carry = c1 | c2;
+    }
+    out
+}
+
+// CIOS Montgomery multiplication: a * b * 2^(-64N) mod p, with inv = -p^(-1) mod 2^64
+#[inline]
+pub(crate) fn mont_mul<const N: usize>(a: &[u64; N], b: &[u64; N], p: &[u64; N], inv: u64) -> [u64; N] {
+    let mut t = [0u64; N];
+    let mut t_n = 0u64;
+    for &ai in a {
+        let mut c = 0u64;
+        for j in 0..N {
+            let uv = t[j] as u128 + ai as u128 * b[j] as u128 + c as u128;
+            t[j] = uv as u64; c = (uv >> 64) as u64;
+        }
+        let uv = t_n as u128 + c as u128;
+        t_n = uv as u64;
+        let t_n1 = (uv >> 64) as u64;
+
+        let m = t[0].wrapping_mul(inv);
+        let uv = t[0] as u128 + m as u128 * p[0] as u128;
+        let mut c = (uv >> 64) as u64;
+        for j in 1..N {
+            let uv = t[j] as u128 + m as u128 * p[j] as u128 + c as u128;
+            t[j - 1] = uv as u64; c = (uv >> 64) as u64;
+        }
+        let uv = t_n as u128 + c as u128;
+        t[N - 1] = uv as u64;
+        t_n = t_n1 + (uv >> 64) as u64;
+    }
+    if t_n != 0 || geq(&t, p) { sub_limbs(&t, p).0 } else { t }
+}
+
+// Defines a 4-limb Montgomery-form prime field type implementing `PrimeField`.
+// `r` is 2^256 mod p (i.e. ONE), `r2` is 2^512 mod p, `inv` is -p^(-1) mod 2^64,
+// `generator` is given in Montgomery form.
+macro_rules! montgomery_field {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        modulus = $modulus:expr,
+        r = $r:expr,
+        r2 = $r2:expr,
+        inv = $inv:expr,
+        generator = $generator:expr,
+        bits = $bits:expr,
+        two_adicity = $two_adicity:expr $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub struct $name([u64; 4]); // Montgomery form, always < p
+
+        impl $name {
+            const P: [u64; 4] = $modulus;
+            const R2: [u64; 4] = $r2;
+            const INV: u64 = $inv;
+
+            // from little-endian canonical limbs; None if >= p
+            pub fn from_limbs(limbs: [u64; 4]) -> Option<Self> {
+                if $crate::field::geq(&limbs, &Self::P) { return None; }
+                Some(Self($crate::field::mont_mul(&limbs, &Self::R2, &Self::P, Self::INV)))
+            }
+            pub fn to_limbs(&self) -> [u64; 4] {
+                $crate::field::mont_mul(&self.0, &[1, 0, 0, 0], &Self::P, Self::INV)
+            }
+        }
+
+        impl $crate::field::PrimeField for $name {
+            const ZERO: Self = Self([0; 4]);
+            const ONE: Self = Self($r);
+            const MODULUS: &'static [u64] = &$modulus;
+            const MODULUS_BITS: u32 = $bits;
+            const TWO_ADICITY: u32 = $two_adicity;
+            const NUM_BYTES: usize = 32;
+
+            fn from_u64(x: u64) -> Self {
+                Self($crate::field::mont_mul(&[x, 0, 0, 0], &Self::R2, &Self::P, Self::INV))
+            }
+            fn inverse(&self) -> Option<Self> { self.inverse_fermat() }
+            fn multiplicative_generator() -> Self { Self($generator) }
+            fn to_bytes_le(&self) -> Vec<u8> {
+                self.to_limbs().iter().flat_map(|l| l.to_le_bytes()).collect()
+            }
+            fn from_bytes_le(bytes: &[u8]) -> Option<Self> {
+                if bytes.len() != 32 { return None; }
+                let mut limbs = [0u64; 4];
+                for (l, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+                    *l = u64::from_le_bytes(chunk.try_into().unwrap());
+                }
+                Self::from_limbs(limbs)
+            }
+        }
+
+        impl std::ops::Add for $name {
+            type Output = Self;
+            #[inline]
+            fn add(self, rhs: Self) -> Self { Self($crate::field::add_mod(&self.0, &rhs.0, &Self::P)) }
+        }
+        impl std::ops::Sub for $name {
+            type Output = Self;
+            #[inline]
+            fn sub(self, rhs: Self) -> Self { Self($crate::field::sub_mod(&self.0, &rhs.0, &Self::P)) }
+        }
+        impl std::ops::Mul for $name {
+            type Output = Self;
+            #[inline]
+            fn mul(self, rhs: Self) -> Self { Self($crate::field::mont_mul(&self.0, &rhs.0, &Self::P, Self::INV)) }
+        }
+        impl std::ops::Neg for $name {
+            type Output = Self;
+            #[inline]
+            fn neg(self) -> Self { Self($crate::field::sub_mod(&[0; 4], &self.0, &Self::P)) }
+        }
+        impl std::ops::AddAssign for $name { fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; } }
+        impl std::ops::SubAssign for $name { fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs; } }
+        impl std::ops::MulAssign for $name { fn mul_assign(&mut self, rhs: Self) { *self = *self * rhs; } }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let l = self.to_limbs();
+                write!(f, "0x{:016x}{:016x}{:016x}{:016x}", l[3], l[2], l[1], l[0])
+            }
+        }
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { std::fmt::Display::fmt(self, f) }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Goldilocks;
+
+    fn check_inversion_and_sqrt<F: PrimeField>() {
+        assert_eq!((F::ZERO.inverse_fermat(), F::ZERO.inverse_euclid(), F::ZERO.sqrt()), (None, None, Some(F::ZERO)));
+        let xs: Vec<F> = (0..40u64).map(|i| F::from_u64(i * i * i + 7 * i + 1) - F::from_u64(20)).collect();
+        for x in &xs {
+            let inv = x.inverse_euclid();
+            assert_eq!(inv, x.inverse_fermat());
+            assert_eq!(inv.map(|i| i * *x), Some(F::ONE));
+            let sq = x.square();
+            let r = sq.sqrt().unwrap();
+            assert!(r == *x || r == -*x);
+            assert_eq!(sq.legendre(), 1);
+        }
+        // the generator of F_p^* is never a square
+        let g = F::multiplicative_generator();
+        assert_eq!((g.legendre(), g.sqrt()), (-1, None));
+        assert_eq!((g * F::from_u64(9)).sqrt(), None);
+
+        let mut batch = xs.clone();
+        batch[3] = F::ZERO;
+        batch_inverse(&mut batch);
+        assert_eq!(batch[3], F::ZERO);
+        for (i, (b, x)) in batch.iter().zip(&xs).enumerate().filter(|(i, _)| *i != 3) {
+            assert_eq!(Some(*b), x.inverse(), "index {i}");
+        }
+    }
+
+    #[test]
+    fn goldilocks_inversion_and_sqrt() {
+        check_inversion_and_sqrt::<Goldilocks>();
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn bn254_inversion_and_sqrt() {
+        check_inversion_and_sqrt::<crate::bn254::Fr>();
+    }
+
+    #[cfg(feature = "bls12_381")]
+    #[test]
+    fn bls12_381_inversion_and_sqrt() {
+        check_inversion_and_sqrt::<crate::bls12_381::Fr>();
+    }
+}
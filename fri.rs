@@ -0,0 +1,417 @@
+// fri.rs — the FRI low-degree test (Ben-Sasson, Bentov, Horesh, Riabzev 2018) with
+// Poseidon Merkle commitments, for STARKs over Goldilocks (any two-adic `PrimeField` works).
+//
+// A codeword is the evaluation of a polynomial of degree < d on the coset g·<ω_N>, N = d·blowup,
+// g the field's multiplicative generator, in natural order. With folding arity k and m = N/k,
+// Merkle leaf i of a layer hashes the k values at positions i, i + m, .., i + (k-1)m — the
+// points x·ζ^j (x = g·ω^i, ζ = ω^m) that share x^k. Writing f(y) = Σ_t y^t f_t(y^k), the next
+// layer is f'(x^k) = Σ_t β^t f_t(x^k) on the coset g^k·<ω^k>, at position i.
+//
+// The domain lives in the base field F, the values in an extension E (E = F is allowed):
+// the folding challenges β are drawn from E, so a 64-bit base field does not cap them at
+// 64 bits. Leaves and nodes are DIGEST_LEN-element Poseidon digests (`DigestTree`).
+//
+// Layers are folded while the degree bound exceeds `final_poly_len` and divides by k; the
+// last layer is sent as its coefficients. After the commit phase the prover grinds a
+// proof-of-work nonce, then the queries are drawn from the transcript. The conjectured
+// security is num_queries·log_blowup + grinding_bits bits, capped by the digest and
+// challenge sizes (`conjectured_security_bits`).
+
+use crate::field::{ExtensionField, PrimeField};
+use crate::merkle::{DigestPath, DigestTree};
+use crate::ntt::{coset_intt, root_of_unity};
+use crate::poseidon::{hash_n_to_digest, Digest, Poseidon, DIGEST_LEN};
+use crate::transcript::Transcript;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FriConfig {
+    pub log_blowup: usize,
+    pub log_arity: usize,
+    pub num_queries: usize,
+    pub grinding_bits: u32,
+    // stop folding once the degree bound is at most this
+    pub final_poly_len: usize,
+}
+
+impl Default for FriConfig {
+    // 100 query bits; reached over GoldilocksExt2 / Ext3, 64 over bare Goldilocks
+    fn default() -> Self { Self { log_blowup: 3, log_arity: 2, num_queries: 28, grinding_bits: 16, final_poly_len: 8 } }
+}
+
+impl FriConfig {
+    pub fn blowup(&self) -> usize { 1 << self.log_blowup }
+    pub fn arity(&self) -> usize { 1 << self.log_arity }
+
+    // the query bound, capped by the digests' collision resistance (half their bits) and by
+    // the size of the challenge field E (less the log of the domain size in practice)
+    pub fn conjectured_security_bits<F: PrimeField, E: ExtensionField<F>>(&self) -> usize {
+        let queries = self.num_queries * self.log_blowup + self.grinding_bits as usize;
+        let bits = F::MODULUS_BITS as usize;
+        queries.min(DIGEST_LEN * bits / 2).min(E::DEGREE * bits)
+    }
+
+    // (number of folds, length of the final polynomial) for a degree bound
+    pub fn rounds(&self, degree_bound: usize) -> (usize, usize) {
+        let (mut d, mut r) = (degree_bound, 0);
+        while d > self.final_poly_len && d.is_multiple_of(self.arity()) {
+            d /= self.arity();
+            r += 1;
+        }
+        (r, d)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryLayer<F: PrimeField, E: ExtensionField<F> = F> {
+    // the values of the opened leaf (k of them in a FRI layer); stark.rs reuses this for
+    // its trace and composition openings
+    pub values: Vec<E>,
+    pub siblings: Vec<Digest<F>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FriProof<F: PrimeField, E: ExtensionField<F> = F> {
+    pub layer_roots: Vec<Digest<F>>,
+    pub final_poly: Vec<E>,
+    pub pow_nonce: u64,
+    // per query, one opening per committed layer
+    pub queries: Vec<Vec<QueryLayer<F, E>>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FriError {
+    Shape,
+    Grinding,
+    Merkle { query: usize, layer: usize },
+    // an opened value disagrees with the fold of the previous layer
+    Fold { query: usize, layer: usize },
+    FinalPoly { query: usize },
+}
+
+impl fmt::Display for FriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FriError::Shape => write!(f, "proof does not match the configuration"),
+            FriError::Grinding => write!(f, "invalid proof-of-work nonce"),
+            FriError::Merkle { query, layer } => write!(f, "query {query}: bad Merkle opening in layer {layer}"),
+            FriError::Fold { query, layer } => write!(f, "query {query}: layer {layer} is not the fold of layer {}", layer - 1),
+            FriError::FinalPoly { query } => write!(f, "query {query}: final polynomial mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for FriError {}
+
+// evaluations of a polynomial of degree < degree_bound (coefficients, zero-padded) on the
+// FRI domain
+pub fn codeword<F: PrimeField>(config: &FriConfig, coeffs: &[F], degree_bound: usize) -> Vec<F> {
+    assert!(coeffs.len() <= degree_bound && degree_bound.is_power_of_two(), "bad degree bound {degree_bound}");
+    let mut padded = coeffs.to_vec();
+    padded.resize(degree_bound, F::ZERO);
+    crate::ntt::lde_from_coeffs(&padded, config.blowup(), F::multiplicative_generator())
+}
+
+// Σ_t β^t f_t(x^k) from the values of f at x·ζ^j, j < k
+fn fold_coset<F: PrimeField, E: ExtensionField<F>>(values: &[E], x: F, zeta_inv: F, beta: E) -> E {
+    let k = values.len();
+    let step = beta * E::from_base(x.inverse().expect("coset points are non-zero"));
+    let (mut acc, mut coef) = (E::from_base(F::ZERO), E::from_base(F::ONE));
+    for t in 0..k {
+        let w = zeta_inv.pow(t as u64);
+        let (mut s, mut wj) = (E::from_base(F::ZERO), F::ONE);
+        for v in values {
+            s += *v * E::from_base(wj);
+            wj *= w;
+        }
+        acc += coef * s;
+        coef *= step;
+    }
+    acc * E::from_base(F::from_u64(k as u64).inverse().unwrap())
+}
+
+pub(crate) fn leaf_hash<F: PrimeField, E: ExtensionField<F>>(perm: &Poseidon<F>, values: &[E]) -> Digest<F> {
+    let coords: Vec<F> = values.iter().flat_map(|v| v.to_base_coeffs()).collect();
+    hash_n_to_digest(perm, &coords)
+}
+
+fn commit_layer<F: PrimeField, E: ExtensionField<F>>(perm: &Poseidon<F>, values: &[E], k: usize) -> DigestTree<F> {
+    let m = values.len() / k;
+    DigestTree::new(perm, (0..m).map(|i| leaf_hash(perm, &coset(values, i, k))).collect())
+}
+
+fn coset<T: Copy>(values: &[T], i: usize, k: usize) -> Vec<T> {
+    let m = values.len() / k;
+    (0..k).map(|j| values[i + j * m]).collect()
+}
+
+pub(crate) fn evaluate<F: PrimeField, E: ExtensionField<F>>(coeffs: &[E], x: E) -> E {
+    coeffs.iter().rev().fold(E::from_base(F::ZERO), |acc, c| acc * x + *c)
+}
+
+// coset_intt coordinate by coordinate, for values in an extension
+pub(crate) fn coset_intt_ext<F: PrimeField, E: ExtensionField<F>>(values: &[E], shift: F) -> Vec<E> {
+    let coords: Vec<Vec<F>> = values.iter().map(|v| v.to_base_coeffs()).collect();
+    let columns: Vec<Vec<F>> = (0..E::DEGREE).map(|d| {
+        let mut c: Vec<F> = coords.iter().map(|v| v[d]).collect();
+        coset_intt(&mut c, shift);
+        c
+    }).collect();
+    (0..values.len()).map(|i| E::from_base_coeffs(&columns.iter().map(|c| c[i]).collect::<Vec<_>>())).collect()
+}
+
+// Prove that `codeword` (on the FRI domain) has degree < codeword.len() / blowup; returns
+// the proof and the queried positions in the first layer.
+pub fn prove<F: PrimeField, E: ExtensionField<F>>(config: &FriConfig, perm: &Poseidon<F>, codeword: &[E], t: &mut Transcript) -> (FriProof<F, E>, Vec<usize>) {
+    let n = codeword.len();
+    assert!(n.is_power_of_two() && n >= config.blowup(), "codeword length {n} is not a power of two multiple of the blowup");
+    let k = config.arity();
+    let (rounds, final_len) = config.rounds(n / config.blowup());
+    let (mut values, mut shift, mut omega) = (codeword.to_vec(), F::multiplicative_generator(), root_of_unity::<F>(n.trailing_zeros()));
+    // (tree, values) per committed layer
+    let mut layers = vec![];
+    for _ in 0..rounds {
+        let tree = commit_layer(perm, &values, k);
+        t.append_fields(b"fri layer root", &tree.root());
+        let beta: E = t.challenge_ext(b"fri fold");
+        let m = values.len() / k;
+        let zeta_inv = omega.pow(m as u64).inverse().unwrap();
+        let mut x = shift;
+        let folded = (0..m).map(|i| {
+            let v = fold_coset(&coset(&values, i, k), x, zeta_inv, beta);
+            x *= omega;
+            v
+        }).collect();
+        layers.push((tree, std::mem::replace(&mut values, folded)));
+        shift = shift.pow(k as u64);
+        omega = omega.pow(k as u64);
+    }
+    // for a codeword that is not low-degree the dropped tail is non-zero and queries fail
+    let final_poly = coset_intt_ext(&values, shift)[..final_len].to_vec();
+    t.append_ext(b"fri final poly", &final_poly);
+
+    let pow_nonce = t.grind(config.grinding_bits);
+    t.append_bytes(b"fri pow nonce", &pow_nonce.to_le_bytes());
+    let positions: Vec<usize> = (0..config.num_queries).map(|_| t.challenge_index(b"fri query", n)).collect();
+    let queries = positions.iter().map(|&start| {
+        let mut p = start;
+        layers.iter().map(|(tree, values)| {
+            let leaf = p % (values.len() / k);
+            p = leaf;
+            QueryLayer { values: coset(values, leaf, k), siblings: tree.path(leaf as u64).siblings }
+        }).collect()
+    }).collect();
+    let layer_roots = layers.iter().map(|(tree, _)| tree.root()).collect();
+    (FriProof { layer_roots, final_poly, pow_nonce, queries }, positions)
+}
+
+// Check a proof for a codeword of degree < degree_bound; returns (position, value) of the
+// first-layer codeword at every query, for callers that must tie it to other commitments.
+pub fn verify<F: PrimeField, E: ExtensionField<F>>(config: &FriConfig, perm: &Poseidon<F>, degree_bound: usize, proof: &FriProof<F, E>, t: &mut Transcript) -> Result<Vec<(usize, E)>, FriError> {
+    // the prover only accepts power-of-two codewords (see `codeword`)
+    if !degree_bound.is_power_of_two() { return Err(FriError::Shape); }
+    let n = degree_bound * config.blowup();
+    let k = config.arity();
+    let (rounds, final_len) = config.rounds(degree_bound);
+    if proof.layer_roots.len() != rounds || proof.final_poly.len() != final_len || proof.queries.len() != config.num_queries {
+        return Err(FriError::Shape);
+    }
+    let betas: Vec<E> = proof.layer_roots.iter().map(|root| {
+        t.append_fields(b"fri layer root", root);
+        t.challenge_ext(b"fri fold")
+    }).collect();
+    t.append_ext(b"fri final poly", &proof.final_poly);
+    if !t.check_grinding(config.grinding_bits, proof.pow_nonce) {
+        return Err(FriError::Grinding);
+    }
+    t.append_bytes(b"fri pow nonce", &proof.pow_nonce.to_le_bytes());
+    let positions: Vec<usize> = (0..config.num_queries).map(|_| t.challenge_index(b"fri query", n)).collect();
+
+    let mut out = vec![];
+    for (q, (&start, layers)) in positions.iter().zip(&proof.queries).enumerate() {
+        if layers.len() != rounds { return Err(FriError::Shape); }
+        let (mut p, mut size) = (start, n);
+        let (mut shift, mut omega) = (F::multiplicative_generator(), root_of_unity::<F>(n.trailing_zeros()));
+        let mut expected = None;
+        for (l, (layer, beta)) in layers.iter().zip(&betas).enumerate() {
+            let m = size / k;
+            if layer.values.len() != k || layer.siblings.len() != m.trailing_zeros() as usize {
+                return Err(FriError::Shape);
+            }
+            let (leaf, slot) = (p % m, p / m);
+            match expected {
+                None => out.push((start, layer.values[slot])),
+                Some(e) if e != layer.values[slot] => return Err(FriError::Fold { query: q, layer: l }),
+                _ => {}
+            }
+            let path = DigestPath { index: leaf as u64, siblings: layer.siblings.clone() };
+            if path.compute_root(perm, leaf_hash(perm, &layer.values)) != proof.layer_roots[l] {
+                return Err(FriError::Merkle { query: q, layer: l });
+            }
+            let zeta_inv = omega.pow(m as u64).inverse().unwrap();
+            expected = Some(fold_coset(&layer.values, shift * omega.pow(leaf as u64), zeta_inv, *beta));
+            p = leaf;
+            size = m;
+            shift = shift.pow(k as u64);
+            omega = omega.pow(k as u64);
+        }
+        let final_value = evaluate(&proof.final_poly, E::from_base(shift * omega.pow(p as u64)));
+        match expected {
+            None => out.push((start, final_value)),
+            Some(e) if e != final_value => return Err(FriError::FinalPoly { query: q }),
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+// ---- Serialization ----
+// u32 little-endian counts, field elements as `to_bytes_le` (extension elements as their
+// coordinates, digests as DIGEST_LEN elements), the nonce as u64 little-endian: roots,
+// final poly, nonce, then per query its layers as (values, siblings).
+impl<F: PrimeField, E: ExtensionField<F>> FriProof<F, E> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        fn put<F: PrimeField>(out: &mut Vec<u8>, count: usize, coords: impl Iterator<Item = F>) {
+            out.extend((count as u32).to_le_bytes());
+            for x in coords { out.extend(x.to_bytes_le()); }
+        }
+        let mut out = vec![];
+        put(&mut out, self.layer_roots.len(), self.layer_roots.iter().flatten().copied());
+        put(&mut out, self.final_poly.len(), self.final_poly.iter().flat_map(|x| x.to_base_coeffs()));
+        out.extend(self.pow_nonce.to_le_bytes());
+        out.extend((self.queries.len() as u32).to_le_bytes());
+        for q in &self.queries {
+            out.extend((q.len() as u32).to_le_bytes());
+            for layer in q {
+                put(&mut out, layer.values.len(), layer.values.iter().flat_map(|x| x.to_base_coeffs()));
+                put(&mut out, layer.siblings.len(), layer.siblings.iter().flatten().copied());
+            }
+        }
+        out
+    }
+
+    // None on truncated, trailing or non-canonical input
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut r = Reader { bytes, pos: 0 };
+        let layer_roots = r.digests()?;
+        let final_poly = r.exts()?;
+        let pow_nonce = u64::from_le_bytes(r.take(8)?.try_into().unwrap());
+        let queries = (0..r.len()?).map(|_| {
+            (0..r.len()?).map(|_| Some(QueryLayer { values: r.exts()?, siblings: r.digests()? })).collect()
+        }).collect::<Option<_>>()?;
+        (r.pos == bytes.len()).then_some(Self { layer_roots, final_poly, pow_nonce, queries })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let out = self.bytes.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(out)
+    }
+    fn len(&mut self) -> Option<usize> { Some(u32::from_le_bytes(self.take(4)?.try_into().unwrap()) as usize) }
+    fn elements<F: PrimeField>(&mut self, n: usize) -> Option<Vec<F>> {
+        (0..n).map(|_| F::from_bytes_le(self.take(F::NUM_BYTES)?)).collect()
+    }
+    fn exts<F: PrimeField, E: ExtensionField<F>>(&mut self) -> Option<Vec<E>> {
+        (0..self.len()?).map(|_| Some(E::from_base_coeffs(&self.elements(E::DEGREE)?))).collect()
+    }
+    fn digests<F: PrimeField>(&mut self) -> Option<Vec<Digest<F>>> {
+        (0..self.len()?).map(|_| self.elements(DIGEST_LEN).map(|d| d.try_into().unwrap())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Goldilocks, GoldilocksExt2};
+
+    type E = GoldilocksExt2;
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+    fn coeffs(n: u64) -> Vec<Goldilocks> { (0..n).map(|i| f(i * i * 31 + 7 * i + 5)).collect() }
+    fn lift(word: &[Goldilocks]) -> Vec<E> { word.iter().map(|x| E::from(*x)).collect() }
+
+    fn config(log_arity: usize) -> FriConfig {
+        FriConfig { log_blowup: 2, log_arity, num_queries: 12, grinding_bits: 6, final_poly_len: 4 }
+    }
+
+    #[test]
+    fn accepts_low_degree_codewords() {
+        let perm = Poseidon::goldilocks_t8();
+        for log_arity in 1..=3 {
+            let config = config(log_arity);
+            // a + X·b for base-field codewords a, b: genuinely extension-valued
+            let (a, b) = (codeword(&config, &coeffs(50), 64), codeword(&config, &coeffs(64)[14..], 64));
+            let word: Vec<E> = a.iter().zip(&b).map(|(a, b)| E::new([*a, *b])).collect();
+            let (proof, positions) = prove(&config, &perm, &word, &mut Transcript::new(b"fri"));
+            let opened = verify(&config, &perm, 64, &proof, &mut Transcript::new(b"fri")).unwrap();
+            assert_eq!(opened, positions.iter().map(|p| (*p, word[*p])).collect::<Vec<_>>());
+
+            let decoded = FriProof::from_bytes(&proof.to_bytes()).unwrap();
+            assert_eq!(decoded, proof);
+            assert!(FriProof::<Goldilocks, E>::from_bytes(&proof.to_bytes()[1..]).is_none());
+        }
+        // a degree bound at or below final_poly_len needs no folding
+        let config = config(2);
+        let word = lift(&codeword(&config, &coeffs(3), 4));
+        let (proof, _) = prove(&config, &perm, &word, &mut Transcript::new(b"fri"));
+        assert!(proof.layer_roots.is_empty());
+        assert!(verify(&config, &perm, 4, &proof, &mut Transcript::new(b"fri")).is_ok());
+        // E = F still works, with challenges from the base field
+        let word = codeword(&config, &coeffs(30), 32);
+        let (proof, _) = prove(&config, &perm, &word, &mut Transcript::new(b"fri"));
+        assert!(verify(&config, &perm, 32, &proof, &mut Transcript::new(b"fri")).is_ok());
+    }
+
+    #[test]
+    fn rejects_high_degree_and_tampering() {
+        let perm = Poseidon::goldilocks_t8();
+        let config = config(2);
+        // degree 100 claimed as < 64: encode on the 256-point domain directly
+        let high = lift(&codeword(&FriConfig { log_blowup: 1, ..config }, &coeffs(100), 128));
+        let (proof, _) = prove(&config, &perm, &high, &mut Transcript::new(b"fri"));
+        assert!(verify(&config, &perm, 64, &proof, &mut Transcript::new(b"fri")).is_err());
+        // a third of the symbols corrupted
+        let mut noisy = lift(&codeword(&config, &coeffs(64), 64));
+        for x in noisy.iter_mut().step_by(3) { *x += E::ONE; }
+        let (proof, _) = prove(&config, &perm, &noisy, &mut Transcript::new(b"fri"));
+        assert!(verify(&config, &perm, 64, &proof, &mut Transcript::new(b"fri")).is_err());
+
+        let word = lift(&codeword(&config, &coeffs(64), 64));
+        let (proof, _) = prove(&config, &perm, &word, &mut Transcript::new(b"fri"));
+        let check = |p: &FriProof<Goldilocks, E>| verify(&config, &perm, 64, p, &mut Transcript::new(b"fri"));
+        let mut bad = proof.clone();
+        bad.queries[3][1].values[0] += E::ONE;
+        assert!(matches!(check(&bad), Err(FriError::Merkle { query: 3, layer: 1 }) | Err(FriError::Fold { query: 3, layer: 1 })));
+        let mut bad = proof.clone();
+        bad.queries[5][0].siblings[1][2] += f(1);
+        assert_eq!(check(&bad), Err(FriError::Merkle { query: 5, layer: 0 }));
+        let mut bad = proof.clone();
+        bad.final_poly[0] += E::ONE;
+        assert!(check(&bad).is_err());
+        let mut bad = proof.clone();
+        bad.pow_nonce += 1;
+        assert!(check(&bad).is_err());
+        assert_eq!(verify(&config, &perm, 32, &proof, &mut Transcript::new(b"fri")), Err(FriError::Shape));
+        for bound in [0, 48, 65] {
+            assert_eq!(verify(&config, &perm, bound, &proof, &mut Transcript::new(b"fri")), Err(FriError::Shape));
+        }
+        assert!(verify(&config, &perm, 64, &proof, &mut Transcript::new(b"other")).is_err());
+    }
+
+    #[test]
+    fn security_is_capped_by_digest_and_field() {
+        let config = FriConfig::default();
+        assert_eq!(config.conjectured_security_bits::<Goldilocks, E>(), 100);
+        // β from the base field: no better than 64 bits however many queries
+        assert_eq!(config.conjectured_security_bits::<Goldilocks, Goldilocks>(), 64);
+        let many = FriConfig { num_queries: 100, ..config };
+        assert_eq!(many.conjectured_security_bits::<Goldilocks, crate::GoldilocksExt3>(), 128);
+    }
+}
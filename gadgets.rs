@@ -0,0 +1,506 @@
+// gadgets.rs — general-purpose Builder gadgets: booleans, bit decomposition, range checks,
+// zero / equality tests and conditional selection.
+//
+// Every gadget computes its own witness from the values already on the Builder (so callers
+// never hand-assign intermediates) and degrades to `None` values in setup mode.
+
+use crate::field::PrimeField;
+use crate::{Builder, LinComb};
+
+impl<F: PrimeField> Builder<F> {
+    // x * (1 - x) = 0
+    pub fn assert_boolean(&mut self, x: usize) {
+        let one_minus_x = LinComb::constant(F::ONE).add_scaled(&LinComb::var(x), -F::ONE);
+        self.constrain(LinComb::var(x), one_minus_x, LinComb::new());
+    }
+
+    pub fn alloc_bit(&mut self, bit: bool) -> usize { self.alloc_bit_opt(Some(bit)) }
+    pub fn alloc_bit_opt(&mut self, bit: Option<bool>) -> usize {
+        let b = self.alloc_opt(bit.map(|b| if b { F::ONE } else { F::ZERO }));
+        self.assert_boolean(b);
+        b
+    }
+
+    // sum(2^i * bits[i]); no constraints
+    pub fn from_bits(&self, bits: &[usize]) -> LinComb<F> {
+        let mut coeff = F::ONE;
+        let mut lc = LinComb::new();
+        for &b in bits {
+            lc = lc.t(b, coeff);
+            coeff = coeff.double();
+        }
+        lc
+    }
+
+    // Little-endian decomposition of `var` into n boolean variables. For n >= MODULUS_BITS the
+    // bits are additionally forced to encode an integer <= p - 1, so x and x + p (which both
+    // fit in n bits when 2^n > p) cannot both satisfy the recomposition.
+    pub fn to_bits_le(&mut self, var: usize, n: usize) -> Vec<usize> {
+        let value_bits = self.value(var).map(|x| field_bits_le(&x, n));
+        let bits: Vec<usize> = (0..n).map(|i| self.alloc_bit_opt(value_bits.as_ref().map(|bs| bs[i]))).collect();
+        let recomposed = self.from_bits(&bits);
+        self.constrain(recomposed, LinComb::constant(F::ONE), LinComb::var(var));
+        if n >= F::MODULUS_BITS as usize {
+            let mut p_minus_1 = F::MODULUS.to_vec();
+            p_minus_1[0] -= 1;
+            self.assert_bits_le_constant(&bits, &p_minus_1);
+        }
+        bits
+    }
+
+    // Enforce sum(2^i bits[i]) <= c for a constant c (little-endian limbs): scanning from the
+    // top, while the prefix of `bits` equals the prefix of c, a 0 in c forces a 0 in bits.
+    pub fn assert_bits_le_constant(&mut self, bits: &[usize], c: &[u64]) {
+        let c_bit = |i: usize| c.get(i / 64).is_some_and(|l| (l >> (i % 64)) & 1 == 1);
+        // a set bit of c at or above bits.len() makes c larger than any n-bit value
+        if (bits.len()..64 * c.len()).any(c_bit) { return; }
+        // product of the bits matched against 1s in c so far (None = empty product = 1)
+        let mut run: Option<LinComb<F>> = None;
+        for i in (0..bits.len()).rev() {
+            let b = LinComb::var(bits[i]);
+            if c_bit(i) {
+                run = Some(match run {
+                    None => b,
+                    Some(r) => LinComb::var(self.mul_lc(&r, &b)),
+                });
+            } else {
+                let guard = run.clone().unwrap_or(LinComb::constant(F::ONE));
+                self.constrain(guard, b, LinComb::new());
+            }
+        }
+    }
+}
+
+// ---- Range checks ----
+// How a value is shown to lie in [0, 2^k):
+//   Bits      k booleans + 1 recomposition                       k + 1 constraints
+//   Limbs(w)  ceil(k/w) limbs, each checked against the table {0..2^w} with the vanishing
+//             polynomial prod(x - i) (2^w - 1 constraints per limb) + 1 recomposition
+//   Table     x itself checked against {0..2^k}                 2^k - 1 constraints
+// Plain R1CS has no lookup argument, so a table of m entries costs m - 1 multiplications:
+// Limbs(w) never beats Bits for w > 1, while Table wins for very small ranges and for
+// intervals whose width is not a power of two. `cheapest_*` picks using these formulas.
+pub const MAX_TABLE_BITS: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeStrategy {
+    Bits,
+    Limbs(usize),
+    Table,
+}
+
+impl RangeStrategy {
+    // constraints added by `Builder::range_check(x, k, self)`
+    pub fn range_cost(self, k: usize) -> usize {
+        match self {
+            RangeStrategy::Bits => k + 1,
+            // range_check rejects these widths; report them as unusable like oversized tables
+            RangeStrategy::Limbs(w) if !(1..=MAX_TABLE_BITS).contains(&w) => usize::MAX,
+            RangeStrategy::Limbs(w) => limb_widths(k, w).iter().map(|&wi| table_cost(1 << wi)).sum::<usize>() + 1,
+            RangeStrategy::Table if k > MAX_TABLE_BITS => usize::MAX,
+            RangeStrategy::Table => table_cost(1 << k),
+        }
+    }
+
+    // constraints added by `Builder::assert_in_range(x, lo, lo + width, self)`
+    pub fn interval_cost(self, width: u64) -> usize {
+        let k = interval_bits(width);
+        match self {
+            RangeStrategy::Table if width > 1 << MAX_TABLE_BITS => usize::MAX,
+            RangeStrategy::Table => table_cost(width as usize),
+            _ if width.is_power_of_two() => self.range_cost(k),
+            _ => self.range_cost(k).saturating_mul(2),
+        }
+    }
+
+    // constraints added by `Builder::less_than(a, b, k, self)`
+    pub fn less_than_cost(self, k: usize) -> usize { self.range_cost(k).saturating_add(1) }
+
+    const CANDIDATES: [RangeStrategy; 5] = [
+        RangeStrategy::Bits, RangeStrategy::Table,
+        RangeStrategy::Limbs(2), RangeStrategy::Limbs(4), RangeStrategy::Limbs(8),
+    ];
+    pub fn cheapest_for_range(k: usize) -> Self {
+        Self::CANDIDATES.into_iter().min_by_key(|s| s.range_cost(k)).unwrap()
+    }
+    pub fn cheapest_for_interval(width: u64) -> Self {
+        Self::CANDIDATES.into_iter().min_by_key(|s| s.interval_cost(width)).unwrap()
+    }
+}
+
+fn table_cost(m: usize) -> usize { m.saturating_sub(1).max(1) }
+
+// widths of the ceil(k/w) limbs covering k bits; the top limb may be narrower
+fn limb_widths(k: usize, w: usize) -> Vec<usize> {
+    (0..k.div_ceil(w)).map(|i| w.min(k - i * w)).collect()
+}
+
+// smallest k with width <= 2^k
+fn interval_bits(width: u64) -> usize {
+    assert!(width > 0, "empty interval");
+    (64 - (width - 1).leading_zeros()) as usize
+}
+
+impl<F: PrimeField> Builder<F> {
+    // x ∈ set via prod(x - s) = 0; costs max(|set| - 1, 1) constraints
+    pub fn assert_in_set(&mut self, x: &LinComb<F>, set: &[F]) {
+        let (last, rest) = set.split_last().expect("empty set");
+        let factor = |s: &F| x.clone().c(-*s);
+        let mut acc = LinComb::constant(F::ONE);
+        for (i, s) in rest.iter().enumerate() {
+            acc = if i == 0 { factor(s) } else { LinComb::var(self.mul_lc(&acc, &factor(s))) };
+        }
+        self.constrain(acc, factor(last), LinComb::new());
+    }
+
+    // Enforce x ∈ [0, 2^k); returns the number of constraints added, which always equals
+    // `strategy.range_cost(k)`.
+    pub fn range_check(&mut self, x: &LinComb<F>, k: usize, strategy: RangeStrategy) -> usize {
+        assert!(k < F::MODULUS_BITS as usize, "range 2^{k} does not fit in the field");
+        let before = self.constraints.len();
+        let value_bits = self.eval_lc(x).map(|v| field_bits_le(&v, k));
+        match strategy {
+            RangeStrategy::Bits => {
+                let bits: Vec<usize> = (0..k).map(|i| self.alloc_bit_opt(value_bits.as_ref().map(|bs| bs[i]))).collect();
+                let recomposed = self.from_bits(&bits);
+                self.constrain(recomposed, LinComb::constant(F::ONE), x.clone());
+            }
+            RangeStrategy::Limbs(w) => {
+                assert!((1..=MAX_TABLE_BITS).contains(&w), "limb width must be in 1..={MAX_TABLE_BITS}");
+                let mut recomposed = LinComb::new();
+                let mut shift = 0;
+                for wi in limb_widths(k, w) {
+                    let limb_value = value_bits.as_ref().map(|bs| {
+                        bs[shift..shift + wi].iter().rev().fold(0u64, |acc, &b| (acc << 1) | b as u64)
+                    });
+                    let limb = LinComb::var(self.alloc_opt(limb_value.map(F::from_u64)));
+                    let table: Vec<F> = (0..1u64 << wi).map(F::from_u64).collect();
+                    self.assert_in_set(&limb, &table);
+                    recomposed = recomposed.add_scaled(&limb, F::from_u64(2).pow(shift as u64));
+                    shift += wi;
+                }
+                self.constrain(recomposed, LinComb::constant(F::ONE), x.clone());
+            }
+            RangeStrategy::Table => {
+                assert!(k <= MAX_TABLE_BITS, "table range checks are limited to 2^{MAX_TABLE_BITS} entries");
+                let table: Vec<F> = (0..1u64 << k).map(F::from_u64).collect();
+                self.assert_in_set(x, &table);
+            }
+        }
+        self.constraints.len() - before
+    }
+
+    // Enforce lo <= x < hi. A width that is not a power of two is covered by two checks,
+    // x - lo < 2^k and x - lo + (2^k - width) < 2^k; Table checks the interval directly.
+    // Returns the number of constraints added (`strategy.interval_cost(hi - lo)`).
+    pub fn assert_in_range(&mut self, x: &LinComb<F>, lo: u64, hi: u64, strategy: RangeStrategy) -> usize {
+        assert!(lo < hi, "empty interval [{lo}, {hi})");
+        let width = hi - lo;
+        let k = interval_bits(width);
+        let y = x.clone().c(-F::from_u64(lo));
+        if strategy == RangeStrategy::Table {
+            assert!(width <= 1 << MAX_TABLE_BITS, "table range checks are limited to 2^{MAX_TABLE_BITS} entries");
+            let before = self.constraints.len();
+            let table: Vec<F> = (lo..hi).map(F::from_u64).collect();
+            self.assert_in_set(x, &table);
+            return self.constraints.len() - before;
+        }
+        let mut cost = self.range_check(&y, k, strategy);
+        if !width.is_power_of_two() {
+            let shifted = y.c(F::from_u64(2).pow(k as u64) - F::from_u64(width));
+            cost += self.range_check(&shifted, k, strategy);
+        }
+        cost
+    }
+
+    // Bit that is 1 iff a < b, assuming a, b ∈ [0, 2^k) (not checked here). With lt the
+    // result, a - b + lt·2^k must lie in [0, 2^k): one booleanity constraint plus one range check.
+    pub fn less_than(&mut self, a: &LinComb<F>, b: &LinComb<F>, k: usize, strategy: RangeStrategy) -> usize {
+        assert!(k + 1 < F::MODULUS_BITS as usize, "comparison of {k}-bit values needs 2^(k+1) < p");
+        let lt_value = self.eval_lc(a).zip(self.eval_lc(b)).map(|(x, y)| canonical_lt(&x, &y));
+        let lt = self.alloc_bit_opt(lt_value);
+        let diff = a.clone().add_scaled(b, -F::ONE).add_scaled(&LinComb::var(lt), F::from_u64(2).pow(k as u64));
+        self.range_check(&diff, k, strategy);
+        lt
+    }
+
+    // a < b, assuming a, b ∈ [0, 2^k): b - a - 1 ∈ [0, 2^k)
+    pub fn assert_less_than(&mut self, a: &LinComb<F>, b: &LinComb<F>, k: usize, strategy: RangeStrategy) -> usize {
+        let diff = b.clone().add_scaled(a, -F::ONE).c(-F::ONE);
+        self.range_check(&diff, k, strategy)
+    }
+}
+
+// ---- Zero tests, selection and boolean logic ----
+// The boolean operations expect inputs that are already constrained to {0, 1} (alloc_bit,
+// is_zero, less_than, ..) and then produce boolean outputs; they do not re-check.
+impl<F: PrimeField> Builder<F> {
+    // 1 iff x = 0. With hint m = 1/x (0 when x = 0): x·m = 1 - out and x·out = 0.
+    pub fn is_zero(&mut self, x: &LinComb<F>) -> usize {
+        let xv = self.eval_lc(x);
+        let m = self.alloc_opt(xv.map(|v| v.inverse().unwrap_or(F::ZERO)));
+        let out = self.alloc_opt(xv.map(|v| if v.is_zero() { F::ONE } else { F::ZERO }));
+        self.constrain(x.clone(), LinComb::var(m), LinComb::constant(F::ONE).add_scaled(&LinComb::var(out), -F::ONE));
+        self.constrain(x.clone(), LinComb::var(out), LinComb::new());
+        out
+    }
+
+    pub fn is_equal(&mut self, a: &LinComb<F>, b: &LinComb<F>) -> usize {
+        self.is_zero(&a.clone().add_scaled(b, -F::ONE))
+    }
+
+    // 1/x, enforced by x·inv = 1 (unsatisfiable when x = 0)
+    pub fn inverse(&mut self, x: &LinComb<F>) -> usize {
+        let inv = self.alloc_opt(self.eval_lc(x).map(|v| v.inverse().unwrap_or(F::ZERO)));
+        self.constrain(x.clone(), LinComb::var(inv), LinComb::constant(F::ONE));
+        inv
+    }
+
+    // a != b, shown by exhibiting 1/(a - b)
+    pub fn assert_not_equal(&mut self, a: &LinComb<F>, b: &LinComb<F>) {
+        self.inverse(&a.clone().add_scaled(b, -F::ONE));
+    }
+
+    // cond ? a : b = b + cond·(a - b); one constraint
+    pub fn select(&mut self, cond: &LinComb<F>, a: &LinComb<F>, b: &LinComb<F>) -> LinComb<F> {
+        let t = self.mul_lc(cond, &a.clone().add_scaled(b, -F::ONE));
+        b.clone().add_scaled(&LinComb::var(t), F::ONE)
+    }
+
+    pub fn not(&self, x: &LinComb<F>) -> LinComb<F> {
+        LinComb::constant(F::ONE).add_scaled(x, -F::ONE)
+    }
+    pub fn and(&mut self, x: &LinComb<F>, y: &LinComb<F>) -> LinComb<F> {
+        LinComb::var(self.mul_lc(x, y))
+    }
+    // x + y - x·y
+    pub fn or(&mut self, x: &LinComb<F>, y: &LinComb<F>) -> LinComb<F> {
+        let xy = self.mul_lc(x, y);
+        x.clone().add_scaled(y, F::ONE).add_scaled(&LinComb::var(xy), -F::ONE)
+    }
+    // x + y - 2·x·y
+    pub fn xor(&mut self, x: &LinComb<F>, y: &LinComb<F>) -> LinComb<F> {
+        let xy = self.mul_lc(x, y);
+        x.clone().add_scaled(y, F::ONE).add_scaled(&LinComb::var(xy), -F::from_u64(2))
+    }
+}
+
+// integer comparison of canonical representatives
+fn canonical_lt<F: PrimeField>(x: &F, y: &F) -> bool {
+    x.to_bytes_le().iter().rev().lt(y.to_bytes_le().iter().rev())
+}
+
+// canonical little-endian bits of x, zero-padded / truncated to n
+pub(crate) fn field_bits_le<F: PrimeField>(x: &F, n: usize) -> Vec<bool> {
+    let mut bits: Vec<bool> = x.to_bytes_le().iter()
+        .flat_map(|b| (0..8).map(move |i| (b >> i) & 1 == 1))
+        .collect();
+    bits.resize(n, false);
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::goldilocks::P;
+    use crate::{verify, Goldilocks};
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+
+    #[test]
+    fn booleans() {
+        for (v, ok) in [(0, true), (1, true), (2, false), (P - 1, false)] {
+            let mut b = Builder::new();
+            let x = b.alloc(f(v));
+            b.assert_boolean(x);
+            assert_eq!(verify(&b, &b.witness()), ok, "x = {v}");
+        }
+        let mut b = Builder::<Goldilocks>::new();
+        let bit = b.alloc_bit(true);
+        assert_eq!((b.value(bit), b.constraints.len()), (Some(f(1)), 1));
+    }
+
+    #[test]
+    fn bit_decomposition_roundtrip() {
+        let mut b = Builder::new();
+        let x = b.alloc(f(0b1011_0110));
+        let bits = b.to_bits_le(x, 10);
+        assert_eq!(bits.iter().map(|v| b.value(*v).unwrap().to_canonical_u64()).collect::<Vec<_>>(),
+                   vec![0, 1, 1, 0, 1, 1, 0, 1, 0, 0]);
+        let back = b.from_bits(&bits);
+        assert_eq!(b.eval_lc(&back), Some(f(0b1011_0110)));
+        assert!(verify(&b, &b.witness()));
+
+        // 300 does not fit in 8 bits
+        let mut b = Builder::new();
+        let x = b.alloc(f(300));
+        b.to_bits_le(x, 8);
+        assert!(!verify(&b, &b.witness()));
+    }
+
+    #[test]
+    fn range_checks_match_reported_costs() {
+        for strategy in [RangeStrategy::Bits, RangeStrategy::Limbs(3), RangeStrategy::Table] {
+            for k in [0, 1, 5, 8] {
+                for (v, ok) in [(0, true), ((1u64 << k) - 1, true), (1 << k, false), (P - 1, false)] {
+                    let mut b = Builder::new();
+                    let x = LinComb::var(b.alloc(f(v)));
+                    let cost = b.range_check(&x, k, strategy);
+                    assert_eq!((cost, b.constraints.len()), (strategy.range_cost(k), strategy.range_cost(k)));
+                    assert_eq!(verify(&b, &b.witness()), ok, "{strategy:?} k = {k} x = {v}");
+                }
+            }
+        }
+        assert_eq!(RangeStrategy::Limbs(3).range_cost(8), 7 + 7 + 3 + 1);
+        assert_eq!(RangeStrategy::Limbs(0).range_cost(8), usize::MAX);
+        assert_eq!(RangeStrategy::Limbs(64).range_cost(8), usize::MAX);
+        assert_eq!(RangeStrategy::Limbs(0).interval_cost(1000), usize::MAX);
+        assert_eq!(RangeStrategy::cheapest_for_range(1), RangeStrategy::Table);
+        assert_eq!(RangeStrategy::cheapest_for_range(32), RangeStrategy::Bits);
+        assert_eq!(RangeStrategy::cheapest_for_interval(5), RangeStrategy::Table);
+        assert_eq!(RangeStrategy::cheapest_for_interval(1000), RangeStrategy::Bits);
+    }
+
+    #[test]
+    fn intervals() {
+        for strategy in [RangeStrategy::Bits, RangeStrategy::Limbs(2), RangeStrategy::Table] {
+            for (lo, hi) in [(10u64, 15), (10, 18), (0, 1)] {
+                for v in lo.saturating_sub(2)..hi + 2 {
+                    let mut b = Builder::new();
+                    let x = LinComb::var(b.alloc(f(v)));
+                    let cost = b.assert_in_range(&x, lo, hi, strategy);
+                    assert_eq!(cost, strategy.interval_cost(hi - lo));
+                    assert_eq!(verify(&b, &b.witness()), (lo..hi).contains(&v), "{strategy:?} [{lo}, {hi}) x = {v}");
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn full_u64_interval() {
+        use crate::bn254::Fr;
+        // hi - lo = 2^64 - 1 needs k = 64, whose offset 2^64 - width overflows a u64
+        let max = Fr::from_u64(u64::MAX);
+        for (v, ok) in [(Fr::ZERO, true), (max - Fr::ONE, true), (max, false), (max + Fr::ONE, false), (-Fr::ONE, false)] {
+            let mut b = Builder::new();
+            let x = LinComb::var(b.alloc(v));
+            let cost = b.assert_in_range(&x, 0, u64::MAX, RangeStrategy::Bits);
+            assert_eq!(cost, RangeStrategy::Bits.interval_cost(u64::MAX));
+            assert_eq!(verify(&b, &b.witness()), ok, "x = {v}");
+        }
+    }
+
+    #[test]
+    fn comparisons_exhaustive() {
+        for strategy in [RangeStrategy::Bits, RangeStrategy::Limbs(2)] {
+            for x in 0..8 {
+                for y in 0..8 {
+                    let mut b = Builder::new();
+                    let (a, c) = (LinComb::var(b.alloc(f(x))), LinComb::var(b.alloc(f(y))));
+                    let lt = b.less_than(&a, &c, 3, strategy);
+                    assert_eq!(b.constraints.len(), strategy.less_than_cost(3));
+                    assert_eq!(b.value(lt), Some(f((x < y) as u64)));
+                    let w = b.witness();
+                    assert!(verify(&b, &w));
+                    // the opposite answer must not verify
+                    let mut flipped = w.clone();
+                    flipped.values.insert(lt, f((x >= y) as u64));
+                    assert!(!verify(&b, &flipped), "{x} < {y} flipped");
+
+                    let mut b = Builder::new();
+                    let (a, c) = (LinComb::var(b.alloc(f(x))), LinComb::var(b.alloc(f(y))));
+                    b.assert_less_than(&a, &c, 3, strategy);
+                    assert_eq!(verify(&b, &b.witness()), x < y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn zero_and_equality_exhaustive() {
+        for x in 0..4 {
+            for y in 0..4 {
+                let mut b = Builder::new();
+                let (a, c) = (LinComb::var(b.alloc(f(x))), LinComb::var(b.alloc(f(y))));
+                let z = b.is_zero(&a);
+                let eq = b.is_equal(&a, &c);
+                assert_eq!((b.value(z), b.value(eq)), (Some(f((x == 0) as u64)), Some(f((x == y) as u64))));
+                let w = b.witness();
+                assert!(verify(&b, &w));
+                // a prover cannot claim the opposite result, whatever hint it picks
+                for hint in [f(0), f(1), f(x).inverse().unwrap_or(f(7))] {
+                    let mut bad = w.clone();
+                    bad.values.insert(z, f((x != 0) as u64));
+                    bad.values.insert(z - 1, hint); // the hint is allocated just before the output
+                    assert!(!verify(&b, &bad), "is_zero({x}) flipped");
+                }
+
+                let mut b = Builder::new();
+                let (a, c) = (LinComb::var(b.alloc(f(x))), LinComb::var(b.alloc(f(y))));
+                b.assert_not_equal(&a, &c);
+                assert_eq!(verify(&b, &b.witness()), x != y);
+            }
+            let mut b = Builder::new();
+            let a = LinComb::var(b.alloc(f(x)));
+            let inv = b.inverse(&a);
+            assert_eq!(verify(&b, &b.witness()), x != 0);
+            if x != 0 {
+                assert_eq!(b.value(inv).unwrap() * f(x), f(1));
+            }
+        }
+    }
+
+    #[test]
+    fn select_and_boolean_ops_exhaustive() {
+        for x in [false, true] {
+            for y in [false, true] {
+                let mut b = Builder::new();
+                let (bx, by) = (LinComb::var(b.alloc_bit(x)), LinComb::var(b.alloc_bit(y)));
+                let outs = [b.not(&bx), b.and(&bx, &by), b.or(&bx, &by), b.xor(&bx, &by)];
+                let want = [!x, x && y, x || y, x ^ y];
+                for (o, w) in outs.iter().zip(want) {
+                    assert_eq!(b.eval_lc(o), Some(f(w as u64)));
+                }
+                let (a, c) = (LinComb::var(b.alloc(f(10))), LinComb::var(b.alloc(f(20))));
+                let s = b.select(&bx, &a, &c);
+                assert_eq!(b.eval_lc(&s), Some(f(if x { 10 } else { 20 })));
+                assert!(verify(&b, &b.witness()));
+            }
+        }
+    }
+
+    #[test]
+    fn full_width_decomposition_is_canonical() {
+        for v in [0, 5, P - 1, P - 2, 1 << 63, (1 << 32) - 1] {
+            let mut b = Builder::new();
+            let x = b.alloc(f(v));
+            b.to_bits_le(x, 64);
+            assert!(verify(&b, &b.witness()), "x = {v}");
+        }
+        // 5 and 5 + p both fit in 64 bits; feed the alias through the same constraints
+        let alias = 5 + P;
+        let mut b = Builder::new();
+        let x = b.alloc(f(5));
+        let bits: Vec<usize> = (0..64).map(|i| b.alloc_bit((alias >> i) & 1 == 1)).collect();
+        let recomposed = b.from_bits(&bits);
+        assert_eq!(b.eval_lc(&recomposed), Some(f(5)));
+        b.constrain(recomposed, LinComb::constant(f(1)), LinComb::var(x));
+        b.assert_bits_le_constant(&bits, &[P - 1]);
+        assert!(!verify(&b, &b.witness()));
+    }
+
+    #[test]
+    fn short_bits_below_wide_constant() {
+        // any 10-bit value is <= p - 1, whose high set bits lie above the decomposition
+        let mut b = Builder::<Goldilocks>::new();
+        let bits: Vec<usize> = (0..10).map(|i| b.alloc_bit((8 >> i) & 1 == 1)).collect();
+        b.assert_bits_le_constant(&bits, &[P - 1]);
+        assert!(verify(&b, &b.witness()));
+        // c = 8 still constrains 10 bits: 9 > 8
+        let mut b = Builder::<Goldilocks>::new();
+        let bits: Vec<usize> = (0..10).map(|i| b.alloc_bit((9 >> i) & 1 == 1)).collect();
+        b.assert_bits_le_constant(&bits, &[8]);
+        assert!(!verify(&b, &b.witness()));
+    }
+}
@@ -0,0 +1,161 @@
+// goldilocks.rs — the Goldilocks prime field, p = 2^64 - 2^32 + 1.
+// Elements are always kept canonical (< p), so derived Eq/Hash are sound.
+//
+// Reduction uses the special shape of p: 2^64 ≡ 2^32 - 1 and 2^96 ≡ -1 (mod p),
+// so a 128-bit product folds back into 64 bits with a couple of adds/subs.
+
+use crate::field::PrimeField;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+pub const P: u64 = 0xffff_ffff_0000_0001;
+const EPSILON: u64 = 0xffff_ffff; // 2^64 mod p
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Goldilocks(u64);
+
+impl Goldilocks {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1);
+    pub const ORDER: u64 = P;
+
+    pub const fn from_u64(x: u64) -> Self { Self(if x >= P { x - P } else { x }) }
+    pub fn from_u128(x: u128) -> Self { Self(reduce128(x)) }
+    pub const fn to_canonical_u64(self) -> u64 { self.0 }
+    pub fn is_zero(self) -> bool { self.0 == 0 }
+    pub fn square(self) -> Self { self * self }
+
+    pub fn pow(self, mut e: u64) -> Self {
+        let (mut x, mut r) = (self, Self::ONE);
+        while e > 0 {
+            if e & 1 == 1 { r *= x; }
+            x = x.square(); e >>= 1;
+        }
+        r
+    }
+
+    // Fermat: x^(p-2). None for zero.
+    pub fn inverse(self) -> Option<Self> {
+        if self.is_zero() { None } else { Some(self.pow(P - 2)) }
+    }
+}
+
+// Fold a 128-bit value into [0, p).
+#[inline]
+fn reduce128(x: u128) -> u64 {
+    let (x_lo, x_hi) = (x as u64, (x >> 64) as u64);
+    let x_hi_hi = x_hi >> 32;
+    let x_hi_lo = x_hi & EPSILON;
+    // x_lo - x_hi_hi (the 2^96 part is -1)
+    let (mut t0, borrow) = x_lo.overflowing_sub(x_hi_hi);
+    if borrow { t0 -= EPSILON; }
+    // + x_hi_lo * (2^32 - 1)  (the 2^64 part)
+    let t1 = x_hi_lo * EPSILON;
+    let (t2, carry) = t0.overflowing_add(t1);
+    let t2 = if carry { t2 + EPSILON } else { t2 };
+    if t2 >= P { t2 - P } else { t2 }
+}
+
+impl PrimeField for Goldilocks {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1);
+    const MODULUS: &'static [u64] = &[P];
+    const MODULUS_BITS: u32 = 64;
+    const TWO_ADICITY: u32 = 32;
+    const NUM_BYTES: usize = 8;
+
+    fn from_u64(x: u64) -> Self { Self::from_u64(x) }
+    fn inverse(&self) -> Option<Self> { Goldilocks::inverse(*self) }
+    fn multiplicative_generator() -> Self { Self(7) }
+    fn to_bytes_le(&self) -> Vec<u8> { self.0.to_le_bytes().to_vec() }
+    fn from_bytes_le(bytes: &[u8]) -> Option<Self> {
+        let x = u64::from_le_bytes(bytes.try_into().ok()?);
+        (x < P).then_some(Self(x))
+    }
+    fn is_zero(&self) -> bool { self.0 == 0 }
+    fn pow(&self, e: u64) -> Self { Goldilocks::pow(*self, e) }
+}
+
+impl From<u64> for Goldilocks {
+    fn from(x: u64) -> Self { Self::from_u64(x) }
+}
+
+impl Add for Goldilocks {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        // both < p, so on overflow the wrapped sum plus 2^64 mod p is already < p
+        let (s, o) = self.0.overflowing_add(rhs.0);
+        if o { Self(s + EPSILON) } else { Self::from_u64(s) }
+    }
+}
+
+impl Sub for Goldilocks {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        let (d, b) = self.0.overflowing_sub(rhs.0);
+        Self(if b { d - EPSILON } else { d })
+    }
+}
+
+impl Mul for Goldilocks {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self { Self(reduce128(self.0 as u128 * rhs.0 as u128)) }
+}
+
+impl Neg for Goldilocks {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self { if self.0 == 0 { self } else { Self(P - self.0) } }
+}
+
+impl AddAssign for Goldilocks { fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; } }
+impl SubAssign for Goldilocks { fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs; } }
+impl MulAssign for Goldilocks { fn mul_assign(&mut self, rhs: Self) { *self = *self * rhs; } }
+
+impl fmt::Display for Goldilocks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.0) }
+}
+
+impl fmt::Debug for Goldilocks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.0) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_near_modulus() {
+        let m1 = Goldilocks::from_u64(P - 1); // -1
+        assert_eq!(m1 * m1, Goldilocks::ONE);
+        assert_eq!(m1 + Goldilocks::ONE, Goldilocks::ZERO);
+        assert_eq!(Goldilocks::ZERO - Goldilocks::ONE, m1);
+        assert_eq!(-m1, Goldilocks::ONE);
+        let (a, b) = (P - 12345, P - 67890);
+        let want = ((a as u128 * b as u128) % P as u128) as u64;
+        assert_eq!((Goldilocks::from_u64(a) * Goldilocks::from_u64(b)).to_canonical_u64(), want);
+        assert_eq!(Goldilocks::from_u128(u128::MAX).to_canonical_u64(), (u128::MAX % P as u128) as u64);
+    }
+
+    #[test]
+    fn inverse_roundtrip() {
+        assert_eq!(Goldilocks::ZERO.inverse(), None);
+        for x in [1u64, 2, 7, 0xdead_beef, P - 1] {
+            let x = Goldilocks::from_u64(x);
+            assert_eq!(x * x.inverse().unwrap(), Goldilocks::ONE);
+        }
+    }
+
+    #[test]
+    fn two_adic_root_and_bytes() {
+        let w = Goldilocks::two_adic_root_of_unity();
+        assert_eq!(w.to_canonical_u64(), 1753635133440165772);
+        assert_eq!(PrimeField::pow(&w, 1 << 31), -Goldilocks::ONE);
+        let x = Goldilocks::from_u64(P - 3);
+        assert_eq!(Goldilocks::from_bytes_le(&x.to_bytes_le()), Some(x));
+        assert_eq!(Goldilocks::from_bytes_le(&P.to_le_bytes()), None);
+    }
+}
@@ -0,0 +1,370 @@
+// groth16.rs — Groth16 (Groth 2016) over BN254 for `Builder` circuits.
+//
+// Variables are split into the instance — ONE (variable 0) followed by `public_inputs` in
+// order — and the witness, every other variable in index order. The QAP is built from the
+// constraints plus one row `x_i · 0 = 0` per instance variable, which keeps the instance
+// polynomials linearly independent (the usual arkworks / bellman layout).
+//
+// Proof and verifying-key points serialize in the EIP-196/197 word layout, and
+// `verify` checks the same pairing-product equation the standard Solidity verifiers do:
+//   e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) = 1.
+//
+// `ToxicWaste::from_seed` and `prove_with_randomness` are deterministic and only meant for
+// tests: anyone who knows the seed can forge proofs. `ToxicWaste::random` / `prove` read
+// 64 bytes of OS randomness from /dev/urandom (the crate has no RNG dependency, so this is
+// Unix-only). A single-party setup still knows the trapdoor; use a proper ceremony for
+// anything real.
+
+use crate::bn254::{msm, multi_pairing, Fq12, Fr, G1, G2};
+use crate::field::PrimeField;
+use crate::poly::{DensePolynomial, Domain, Evaluations};
+use crate::transcript::Transcript;
+use crate::{try_verify, Builder, LinComb, VerifyError};
+use std::fmt;
+
+// ---- R1CS -> QAP ----
+#[derive(Clone, Debug)]
+pub struct Qap<F: PrimeField> {
+    pub domain: Domain<F>,
+    pub num_vars: usize,
+    // instance variables (ONE first) and witness variables
+    pub instance: Vec<usize>,
+    pub witness: Vec<usize>,
+    // one row per constraint, then one per instance variable
+    pub a: Vec<LinComb<F>>,
+    pub b: Vec<LinComb<F>>,
+    pub c: Vec<LinComb<F>>,
+}
+
+impl<F: PrimeField> Qap<F> {
+    pub fn new(builder: &Builder<F>) -> Self {
+        let mut instance = vec![Builder::<F>::ONE];
+        instance.extend(&builder.public_inputs);
+        let witness = (0..builder.next_var).filter(|v| !instance.contains(v)).collect();
+        // constant terms become coefficients of ONE, so every row is linear in the variables
+        let homogenize = |lc: &LinComb<F>| {
+            LinComb { terms: lc.terms.clone(), const_term: F::ZERO }.t(Builder::<F>::ONE, lc.const_term).simplify()
+        };
+        let mut a: Vec<_> = builder.constraints.iter().map(|con| homogenize(&con.a)).collect();
+        let mut b: Vec<_> = builder.constraints.iter().map(|con| homogenize(&con.b)).collect();
+        let mut c: Vec<_> = builder.constraints.iter().map(|con| homogenize(&con.c)).collect();
+        for v in &instance {
+            a.push(LinComb::var(*v));
+            b.push(LinComb::new());
+            c.push(LinComb::new());
+        }
+        Self { domain: Domain::new(a.len()), num_vars: builder.next_var, instance, witness, a, b, c }
+    }
+
+    // (u_j(τ), v_j(τ), w_j(τ)) for every variable j
+    pub fn evaluate_at(&self, tau: F) -> (Vec<F>, Vec<F>, Vec<F>) {
+        let lag = self.domain.lagrange_coeffs_at(tau);
+        let column = |rows: &[LinComb<F>]| {
+            let mut out = vec![F::ZERO; self.num_vars];
+            for (row, l) in rows.iter().zip(&lag) {
+                for (v, coeff) in &row.terms {
+                    out[*v] += *coeff * *l;
+                }
+            }
+            out
+        };
+        (column(&self.a), column(&self.b), column(&self.c))
+    }
+
+    // h(X) = (A(X)·B(X) - C(X)) / Z(X) for a full assignment z; None if z does not satisfy
+    // the constraints (Z does not divide)
+    pub fn h_polynomial(&self, z: &[F]) -> Option<DensePolynomial<F>> {
+        let interpolate = |rows: &[LinComb<F>]| {
+            let mut evals: Vec<F> = rows.iter()
+                .map(|lc| lc.terms.iter().fold(F::ZERO, |acc, (v, c)| acc + *c * z[*v]))
+                .collect();
+            evals.resize(self.domain.size, F::ZERO);
+            Evaluations::new(evals, self.domain.clone()).interpolate()
+        };
+        let (pa, pb, pc) = (interpolate(&self.a), interpolate(&self.b), interpolate(&self.c));
+        let (h, rem) = (&(&pa * &pb) - &pc).divide_by_vanishing(&self.domain);
+        rem.is_zero().then_some(h)
+    }
+}
+
+// ---- Keys and proofs ----
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ToxicWaste {
+    pub tau: Fr,
+    pub alpha: Fr,
+    pub beta: Fr,
+    pub gamma: Fr,
+    pub delta: Fr,
+}
+
+impl ToxicWaste {
+    // deterministic, test-only
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let mut t = Transcript::new(b"delta_zk groth16 toxic waste");
+        t.append_bytes(b"seed", seed);
+        let x: Vec<Fr> = t.challenges(b"trapdoor", 5);
+        Self { tau: x[0], alpha: x[1], beta: x[2], gamma: x[3], delta: x[4] }
+    }
+    pub fn random() -> Self { Self::from_seed(&entropy()) }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    pub alpha_g1: G1,
+    pub beta_g2: G2,
+    pub gamma_g2: G2,
+    pub delta_g2: G2,
+    // [(β·u_i + α·v_i + w_i)(τ) / γ]_1 for the instance variables, ONE first
+    pub ic: Vec<G1>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProvingKey {
+    pub vk: VerifyingKey,
+    pub qap: Qap<Fr>,
+    pub beta_g1: G1,
+    pub delta_g1: G1,
+    // per variable: [u_j(τ)]_1, [v_j(τ)]_1, [v_j(τ)]_2
+    pub a_query: Vec<G1>,
+    pub b_g1_query: Vec<G1>,
+    pub b_g2_query: Vec<G2>,
+    // per witness variable: [(β·u_j + α·v_j + w_j)(τ) / δ]_1
+    pub l_query: Vec<G1>,
+    // [τ^i·Z(τ) / δ]_1 for i < n - 1
+    pub h_query: Vec<G1>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Proof {
+    pub a: G1,
+    pub b: G2,
+    pub c: G1,
+}
+
+impl Proof {
+    // A || B || C, 256 bytes
+    pub fn to_eth_bytes(&self) -> Vec<u8> {
+        [self.a.to_eth_bytes(), self.b.to_eth_bytes(), self.c.to_eth_bytes()].concat()
+    }
+    pub fn from_eth_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 256 { return None; }
+        Some(Self {
+            a: G1::from_eth_bytes(&bytes[..64])?,
+            b: G2::from_eth_bytes(&bytes[64..192])?,
+            c: G1::from_eth_bytes(&bytes[192..])?,
+        })
+    }
+}
+
+impl VerifyingKey {
+    // α || β || γ || δ || IC_0 || IC_1 ..
+    pub fn to_eth_bytes(&self) -> Vec<u8> {
+        let mut out = [self.alpha_g1.to_eth_bytes(), self.beta_g2.to_eth_bytes(),
+                       self.gamma_g2.to_eth_bytes(), self.delta_g2.to_eth_bytes()].concat();
+        for p in &self.ic { out.extend(p.to_eth_bytes()); }
+        out
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Groth16Error {
+    // the builder's assignment is incomplete or does not satisfy its constraints
+    Witness(VerifyError),
+    PublicInputCount { expected: usize, got: usize },
+    // the builder is not the circuit the proving key was set up for
+    CircuitMismatch,
+    // the pairing check failed
+    InvalidProof,
+}
+
+impl fmt::Display for Groth16Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Groth16Error::Witness(e) => write!(f, "cannot prove: {e}"),
+            Groth16Error::PublicInputCount { expected, got } => write!(f, "expected {expected} public inputs, got {got}"),
+            Groth16Error::CircuitMismatch => write!(f, "circuit does not match the proving key"),
+            Groth16Error::InvalidProof => write!(f, "proof does not verify"),
+        }
+    }
+}
+
+impl std::error::Error for Groth16Error {}
+
+// ---- Setup / prove / verify ----
+// Only the builder's constraints and public-input layout are used, so a
+// `Builder::setup()` circuit is enough.
+pub fn setup(builder: &Builder<Fr>) -> ProvingKey { setup_with_toxic_waste(builder, &ToxicWaste::random()) }
+
+pub fn setup_with_toxic_waste(builder: &Builder<Fr>, tw: &ToxicWaste) -> ProvingKey {
+    let qap = Qap::new(builder);
+    let (u, v, w) = qap.evaluate_at(tw.tau);
+    let (g1, g2) = (G1::generator(), G2::generator());
+    let (gamma_inv, delta_inv) = (tw.gamma.inverse().unwrap(), tw.delta.inverse().unwrap());
+    let combined = |j: usize| tw.beta * u[j] + tw.alpha * v[j] + w[j];
+
+    let ic = qap.instance.iter().map(|j| g1.mul(&(combined(*j) * gamma_inv))).collect();
+    let l_query = qap.witness.iter().map(|j| g1.mul(&(combined(*j) * delta_inv))).collect();
+    let z_over_delta = qap.domain.evaluate_vanishing(tw.tau) * delta_inv;
+    let h_query = std::iter::successors(Some(z_over_delta), |x| Some(*x * tw.tau))
+        .take(qap.domain.size - 1)
+        .map(|x| g1.mul(&x))
+        .collect();
+    ProvingKey {
+        vk: VerifyingKey {
+            alpha_g1: g1.mul(&tw.alpha),
+            beta_g2: g2.mul(&tw.beta),
+            gamma_g2: g2.mul(&tw.gamma),
+            delta_g2: g2.mul(&tw.delta),
+            ic,
+        },
+        beta_g1: g1.mul(&tw.beta),
+        delta_g1: g1.mul(&tw.delta),
+        a_query: u.iter().map(|x| g1.mul(x)).collect(),
+        b_g1_query: v.iter().map(|x| g1.mul(x)).collect(),
+        b_g2_query: v.iter().map(|x| g2.mul(x)).collect(),
+        l_query,
+        h_query,
+        qap,
+    }
+}
+
+pub fn prove(pk: &ProvingKey, builder: &Builder<Fr>) -> Result<Proof, Groth16Error> {
+    let mut t = Transcript::new(b"delta_zk groth16 prover");
+    t.append_bytes(b"entropy", &entropy());
+    prove_with_randomness(pk, builder, t.challenge(b"r"), t.challenge(b"s"))
+}
+
+// deterministic blinding, test-only
+pub fn prove_with_randomness(pk: &ProvingKey, builder: &Builder<Fr>, r: Fr, s: Fr) -> Result<Proof, Groth16Error> {
+    try_verify(builder, &builder.witness()).map_err(Groth16Error::Witness)?;
+    let qap = &pk.qap;
+    let mut instance = vec![Builder::<Fr>::ONE];
+    instance.extend(&builder.public_inputs);
+    if builder.next_var != qap.num_vars || builder.constraints.len() + instance.len() != qap.a.len() || instance != qap.instance {
+        return Err(Groth16Error::CircuitMismatch);
+    }
+    let z: Vec<Fr> = (0..builder.next_var).map(|v| builder.value(v).unwrap_or(Fr::ZERO)).collect();
+    // same shape but different constraints: the assignment is no QAP solution for pk
+    let h = qap.h_polynomial(&z).ok_or(Groth16Error::CircuitMismatch)?;
+
+    let vk = &pk.vk;
+    let a = vk.alpha_g1 + msm(&pk.a_query, &z) + pk.delta_g1.mul(&r);
+    let b = vk.beta_g2 + msm(&pk.b_g2_query, &z) + vk.delta_g2.mul(&s);
+    let b1 = pk.beta_g1 + msm(&pk.b_g1_query, &z) + pk.delta_g1.mul(&s);
+    let zw: Vec<Fr> = pk.qap.witness.iter().map(|v| z[*v]).collect();
+    let c = msm(&pk.l_query, &zw) + msm(&pk.h_query[..h.coeffs.len()], &h.coeffs)
+        + a.mul(&s) + b1.mul(&r) - pk.delta_g1.mul(&(r * s));
+    Ok(Proof { a, b, c })
+}
+
+// `public` in `builder.public_inputs` order (ONE is implied)
+pub fn verify(vk: &VerifyingKey, public: &[Fr], proof: &Proof) -> Result<(), Groth16Error> {
+    if public.len() + 1 != vk.ic.len() {
+        // a malformed key with an empty IC matches no input count
+        return Err(Groth16Error::PublicInputCount { expected: vk.ic.len().saturating_sub(1), got: public.len() });
+    }
+    let vk_x = vk.ic[0] + msm(&vk.ic[1..], public);
+    let check = multi_pairing(&[
+        (-proof.a, proof.b),
+        (vk.alpha_g1, vk.beta_g2),
+        (vk_x, vk.gamma_g2),
+        (proof.c, vk.delta_g2),
+    ]);
+    if check == Fq12::ONE { Ok(()) } else { Err(Groth16Error::InvalidProof) }
+}
+
+// 64 bytes from the OS CSPRNG
+fn entropy() -> Vec<u8> {
+    use std::io::Read;
+    let mut buf = vec![0u8; 64];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut buf))
+        .expect("cannot read OS randomness from /dev/urandom");
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poseidon::Poseidon;
+
+    fn f(x: u64) -> Fr { Fr::from_u64(x) }
+
+    // x^3 + x + 5 = out, out public
+    fn cubic(x: Option<Fr>) -> Builder<Fr> {
+        let mut b = if x.is_some() { Builder::new() } else { Builder::setup() };
+        let xv = b.alloc_opt(x);
+        let x3 = b.pow_lc(&LinComb::var(xv), 3);
+        let out = b.alloc_public_opt(x.map(|x| x * x * x + x + f(5)));
+        b.constrain(x3.add_scaled(&LinComb::var(xv), f(1)).c(f(5)), LinComb::constant(f(1)), LinComb::var(out));
+        b
+    }
+
+    #[test]
+    fn cubic_circuit() {
+        let pk = setup_with_toxic_waste(&cubic(None), &ToxicWaste::from_seed(b"test"));
+        let proof = prove_with_randomness(&pk, &cubic(Some(f(3))), f(11), f(22)).unwrap();
+        assert_eq!(verify(&pk.vk, &[f(35)], &proof), Ok(()));
+        assert_eq!(verify(&pk.vk, &[f(36)], &proof), Err(Groth16Error::InvalidProof));
+        assert_eq!(verify(&pk.vk, &[], &proof), Err(Groth16Error::PublicInputCount { expected: 1, got: 0 }));
+        let forged = Proof { c: proof.c + G1::generator(), ..proof };
+        assert_eq!(verify(&pk.vk, &[f(35)], &forged), Err(Groth16Error::InvalidProof));
+
+        // proofs are re-randomized but all verify; the wire format round-trips
+        let other = prove(&pk, &cubic(Some(f(3)))).unwrap();
+        assert_ne!(other, proof);
+        assert_eq!(Proof::from_eth_bytes(&other.to_eth_bytes()), Some(other));
+        assert_eq!(verify(&pk.vk, &[f(35)], &other), Ok(()));
+        assert_eq!(pk.vk.to_eth_bytes().len(), 64 + 3 * 128 + 2 * 64);
+
+        // an unsatisfied assignment is refused before any group work
+        let mut bad = cubic(Some(f(3)));
+        bad.values[2] = Some(f(4));
+        assert!(matches!(prove(&pk, &bad), Err(Groth16Error::Witness(VerifyError::Unsatisfied(_)))));
+
+        // a satisfied builder for another circuit is refused, whether or not the shape matches
+        let mut wider = cubic(Some(f(3)));
+        wider.alloc(f(1));
+        assert_eq!(prove(&pk, &wider), Err(Groth16Error::CircuitMismatch));
+        let mut shifted = cubic(Some(f(3)));
+        // x^3 + x + 4 = out: same variables and rows, different polynomials
+        shifted.constraints.last_mut().unwrap().a.const_term -= f(1);
+        let out = shifted.public_inputs[0];
+        shifted.values[out] = Some(f(34));
+        assert!(crate::verify(&shifted, &shifted.witness()));
+        assert_eq!(prove(&pk, &shifted), Err(Groth16Error::CircuitMismatch));
+
+        let empty = VerifyingKey { ic: vec![], ..pk.vk.clone() };
+        assert_eq!(verify(&empty, &[f(35)], &proof), Err(Groth16Error::PublicInputCount { expected: 0, got: 1 }));
+    }
+
+    #[test]
+    fn qap_divides_exactly_for_satisfying_assignments() {
+        let b = cubic(Some(f(3)));
+        let qap = Qap::new(&b);
+        let out = b.public_inputs[0];
+        assert_eq!((qap.instance.clone(), qap.a.len()), (vec![0, out], b.constraints.len() + 2));
+        let mut z: Vec<Fr> = (0..b.next_var).map(|v| b.value(v).unwrap()).collect();
+        assert!(qap.h_polynomial(&z).is_some());
+        z[out] += f(1);
+        assert!(qap.h_polynomial(&z).is_none());
+    }
+
+    #[test]
+    fn poseidon_preimage() {
+        let perm = Poseidon::bn254_t3();
+        let circuit = |l: Option<Fr>, r: Option<Fr>| {
+            let mut b = if l.is_some() { Builder::new() } else { Builder::setup() };
+            let (lv, rv) = (b.alloc_opt(l), b.alloc_opt(r));
+            let out = b.poseidon_compress(&perm, &LinComb::var(lv), &LinComb::var(rv));
+            let hash = b.alloc_public_opt(b.eval_lc(&out));
+            b.constrain(out, LinComb::constant(f(1)), LinComb::var(hash));
+            b
+        };
+        let pk = setup_with_toxic_waste(&circuit(None, None), &ToxicWaste::from_seed(b"poseidon"));
+        let hash = crate::poseidon::two_to_one(&perm, f(1), f(2));
+        let proof = prove(&pk, &circuit(Some(f(1)), Some(f(2)))).unwrap();
+        assert_eq!(verify(&pk.vk, &[hash], &proof), Ok(()));
+        assert!(verify(&pk.vk, &[hash + f(1)], &proof).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+// keccak.rs — Keccak-f[1600] and SHAKE256 (FIPS 202), used to derive Rescue-Prime constants.
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+// rotation offsets and lane order for the combined rho + pi step
+const RHO: [u32; 24] = [1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44];
+const PI: [usize; 24] = [10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1];
+
+pub fn keccak_f(a: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // theta
+        let c: [u64; 5] = std::array::from_fn(|x| a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20]);
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 { a[x + 5 * y] ^= d; }
+        }
+        // rho + pi
+        let mut last = a[1];
+        for (r, p) in RHO.iter().zip(PI) {
+            let tmp = a[p];
+            a[p] = last.rotate_left(*r);
+            last = tmp;
+        }
+        // chi
+        for y in 0..5 {
+            let row: [u64; 5] = std::array::from_fn(|x| a[x + 5 * y]);
+            for x in 0..5 { a[x + 5 * y] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]); }
+        }
+        // iota
+        a[0] ^= rc;
+    }
+}
+
+// SHAKE256 XOF: rate 136 bytes, domain suffix 0x1f
+pub fn shake256(input: &[u8], out_len: usize) -> Vec<u8> {
+    const RATE: usize = 136;
+    let mut state = [0u64; 25];
+    let xor_block = |state: &mut [u64; 25], block: &[u8]| {
+        for (i, chunk) in block.chunks(8).enumerate() {
+            let mut lane = [0u8; 8];
+            lane[..chunk.len()].copy_from_slice(chunk);
+            state[i] ^= u64::from_le_bytes(lane);
+        }
+    };
+    let mut blocks = input.chunks_exact(RATE);
+    for block in &mut blocks {
+        xor_block(&mut state, block);
+        keccak_f(&mut state);
+    }
+    let mut last = blocks.remainder().to_vec();
+    last.resize(RATE, 0);
+    last[blocks.remainder().len()] ^= 0x1f;
+    last[RATE - 1] ^= 0x80;
+    xor_block(&mut state, &last);
+    keccak_f(&mut state);
+
+    let mut out = Vec::with_capacity(out_len);
+    loop {
+        for lane in &state[..RATE / 8] {
+            for byte in lane.to_le_bytes() {
+                if out.len() == out_len { return out; }
+                out.push(byte);
+            }
+        }
+        keccak_f(&mut state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shake256_known_answers() {
+        let hex = |b: &[u8]| b.iter().map(|x| format!("{x:02x}")).collect::<String>();
+        assert_eq!(hex(&shake256(b"", 32)), "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f");
+        assert_eq!(hex(&shake256(b"abc", 32)), "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739");
+        // output longer than one block, input longer than one block
+        let long = shake256(&[0xa3; 200], 300);
+        assert_eq!(hex(&long[284..]), "cea847156d277ad0e141c24c7839064c");
+    }
+}
@@ -0,0 +1,327 @@
+// merkle.rs — Poseidon Merkle trees (dense, sparse and wide-digest) and in-circuit membership proofs.
+//
+// Nodes are combined with `poseidon::two_to_one`. A path is the list of siblings from the
+// leaf level up; direction bit i is 1 when the node at level i is a right child
+// (bit i of the leaf index).
+
+use crate::field::PrimeField;
+use crate::poseidon::{compress_digests, two_to_one, Digest, Poseidon};
+use crate::{Builder, LinComb};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerklePath<F: PrimeField> {
+    pub index: u64,
+    // siblings from the leaf level up to just below the root
+    pub siblings: Vec<F>,
+}
+
+impl<F: PrimeField> MerklePath<F> {
+    pub fn directions(&self) -> Vec<bool> {
+        (0..self.siblings.len()).map(|i| (self.index >> i) & 1 == 1).collect()
+    }
+
+    pub fn compute_root(&self, perm: &Poseidon<F>, leaf: F) -> F {
+        self.siblings.iter().zip(self.directions()).fold(leaf, |node, (sib, right)| {
+            if right { two_to_one(perm, *sib, node) } else { two_to_one(perm, node, *sib) }
+        })
+    }
+}
+
+pub fn verify_path<F: PrimeField>(perm: &Poseidon<F>, root: F, leaf: F, path: &MerklePath<F>) -> bool {
+    path.compute_root(perm, leaf) == root
+}
+
+// ---- Dense tree ----
+#[derive(Clone, Debug)]
+pub struct MerkleTree<F: PrimeField> {
+    perm: Poseidon<F>,
+    // layers[0] = 2^depth leaves, layers[depth] = [root]
+    layers: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> MerkleTree<F> {
+    // missing leaves are zero
+    pub fn new(perm: Poseidon<F>, depth: usize, leaves: &[F]) -> Self {
+        assert!(depth < 64, "depth must be < 64");
+        assert!(leaves.len() <= 1 << depth, "too many leaves for depth {depth}");
+        let mut level = leaves.to_vec();
+        level.resize(1 << depth, F::ZERO);
+        let mut layers = vec![level];
+        for _ in 0..depth {
+            let next = layers.last().unwrap().chunks(2).map(|p| two_to_one(&perm, p[0], p[1])).collect();
+            layers.push(next);
+        }
+        Self { perm, layers }
+    }
+
+    pub fn depth(&self) -> usize { self.layers.len() - 1 }
+    pub fn root(&self) -> F { self.layers[self.depth()][0] }
+    pub fn leaf(&self, index: u64) -> F { self.layers[0][index as usize] }
+    pub fn hasher(&self) -> &Poseidon<F> { &self.perm }
+
+    pub fn path(&self, index: u64) -> MerklePath<F> {
+        let siblings = (0..self.depth()).map(|l| self.layers[l][((index >> l) ^ 1) as usize]).collect();
+        MerklePath { index, siblings }
+    }
+
+    // replace a leaf and rehash the nodes above it
+    pub fn update(&mut self, index: u64, leaf: F) {
+        let mut i = index as usize;
+        self.layers[0][i] = leaf;
+        for l in 0..self.depth() {
+            let (left, right) = (self.layers[l][i & !1], self.layers[l][i | 1]);
+            i >>= 1;
+            self.layers[l + 1][i] = two_to_one(&self.perm, left, right);
+        }
+    }
+}
+
+// ---- Sparse tree keyed by field elements ----
+// A leaf lives at the position given by the low `depth` bits of its key's canonical
+// encoding; absent leaves are zero, so storing zero is the same as deleting.
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree<F: PrimeField> {
+    perm: Poseidon<F>,
+    depth: usize,
+    // empty[l] = root of an all-zero subtree of height l
+    empty: Vec<F>,
+    // (level, path bits above that level) -> node, only for non-empty subtrees
+    nodes: HashMap<(usize, Vec<bool>), F>,
+}
+
+impl<F: PrimeField> SparseMerkleTree<F> {
+    // full-width keys: depth = MODULUS_BITS
+    pub fn new(perm: Poseidon<F>) -> Self { Self::with_depth(perm, F::MODULUS_BITS as usize) }
+
+    pub fn with_depth(perm: Poseidon<F>, depth: usize) -> Self {
+        let mut empty = vec![F::ZERO];
+        for l in 0..depth {
+            empty.push(two_to_one(&perm, empty[l], empty[l]));
+        }
+        Self { perm, depth, empty, nodes: HashMap::new() }
+    }
+
+    pub fn depth(&self) -> usize { self.depth }
+
+    // little-endian position bits of `key`; panics if the key does not fit in `depth` bits
+    pub fn key_bits(&self, key: F) -> Vec<bool> {
+        let bits: Vec<bool> = key.to_bytes_le().iter()
+            .flat_map(|b| (0..8).map(move |i| (b >> i) & 1 == 1))
+            .collect();
+        assert!(bits[self.depth.min(bits.len())..].iter().all(|b| !b), "key does not fit in {} bits", self.depth);
+        let mut bits = bits;
+        bits.resize(self.depth, false);
+        bits
+    }
+
+    fn node(&self, level: usize, bits: &[bool]) -> F {
+        self.nodes.get(&(level, bits[level..].to_vec())).copied().unwrap_or(self.empty[level])
+    }
+
+    pub fn root(&self) -> F { self.node(self.depth, &self.key_bits(F::ZERO)) }
+    pub fn get(&self, key: F) -> F { self.node(0, &self.key_bits(key)) }
+
+    pub fn insert(&mut self, key: F, value: F) {
+        let bits = self.key_bits(key);
+        let mut node = value;
+        for l in 0..=self.depth {
+            let id = (l, bits[l..].to_vec());
+            if node == self.empty[l] { self.nodes.remove(&id); } else { self.nodes.insert(id, node); }
+            if l == self.depth { break; }
+            let mut sib_bits = bits.clone();
+            sib_bits[l] = !sib_bits[l];
+            let sib = self.node(l, &sib_bits);
+            node = if bits[l] { two_to_one(&self.perm, sib, node) } else { two_to_one(&self.perm, node, sib) };
+        }
+    }
+
+    // siblings for `key`; the directions are the key bits
+    pub fn path(&self, key: F) -> Vec<F> {
+        let bits = self.key_bits(key);
+        (0..self.depth).map(|l| {
+            let mut sib_bits = bits.clone();
+            sib_bits[l] = !sib_bits[l];
+            self.node(l, &sib_bits)
+        }).collect()
+    }
+
+    pub fn verify(&self, key: F, value: F, siblings: &[F]) -> bool {
+        let root = siblings.iter().zip(self.key_bits(key)).fold(value, |node, (sib, right)| {
+            if right { two_to_one(&self.perm, *sib, node) } else { two_to_one(&self.perm, node, *sib) }
+        });
+        root == self.root()
+    }
+}
+
+// ---- Wide-digest tree ----
+// The same layout with `Digest`s as nodes, for commitments over small fields (fri.rs,
+// stark.rs). The caller hashes its leaves (`hash_n_to_digest`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigestPath<F: PrimeField> {
+    pub index: u64,
+    pub siblings: Vec<Digest<F>>,
+}
+
+impl<F: PrimeField> DigestPath<F> {
+    pub fn compute_root(&self, perm: &Poseidon<F>, leaf: Digest<F>) -> Digest<F> {
+        self.siblings.iter().enumerate().fold(leaf, |node, (i, sib)| {
+            if (self.index >> i) & 1 == 1 { compress_digests(perm, sib, &node) } else { compress_digests(perm, &node, sib) }
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DigestTree<F: PrimeField> {
+    // layers[0] = leaves, layers[depth] = [root]
+    layers: Vec<Vec<Digest<F>>>,
+}
+
+impl<F: PrimeField> DigestTree<F> {
+    pub fn new(perm: &Poseidon<F>, leaves: Vec<Digest<F>>) -> Self {
+        assert!(leaves.len().is_power_of_two(), "need a power-of-two number of leaves");
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers.last().unwrap().chunks(2).map(|p| compress_digests(perm, &p[0], &p[1])).collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    pub fn depth(&self) -> usize { self.layers.len() - 1 }
+    pub fn root(&self) -> Digest<F> { self.layers[self.depth()][0] }
+
+    pub fn path(&self, index: u64) -> DigestPath<F> {
+        let siblings = (0..self.depth()).map(|l| self.layers[l][((index >> l) ^ 1) as usize]).collect();
+        DigestPath { index, siblings }
+    }
+}
+
+// ---- In-circuit membership ----
+impl<F: PrimeField> Builder<F> {
+    // Recompute the root from `leaf`, `siblings` and boolean `directions` (1 = node is the
+    // right child). Each level costs one booleanity constraint, one constraint for the
+    // conditional swap and one Poseidon compression.
+    pub fn merkle_root(&mut self, perm: &Poseidon<F>, leaf: usize, siblings: &[usize], directions: &[usize]) -> LinComb<F> {
+        assert_eq!(siblings.len(), directions.len(), "one direction bit per level");
+        let mut node = LinComb::var(leaf);
+        for (&sib, &d) in siblings.iter().zip(directions) {
+            self.assert_boolean(d);
+            let d_lc = LinComb::var(d);
+            // t = d * (sib - node); left = node + t, right = sib - t
+            let diff = LinComb::var(sib).add_scaled(&node, -F::ONE);
+            let t = LinComb::var(self.mul_lc(&d_lc, &diff));
+            let left = node.clone().add_scaled(&t, F::ONE);
+            let right = LinComb::var(sib).add_scaled(&t, -F::ONE);
+            node = self.poseidon_compress(perm, &left, &right);
+        }
+        node
+    }
+
+    // enforce that `leaf` is in the tree with root `root` (typically a public input)
+    pub fn merkle_membership(&mut self, perm: &Poseidon<F>, leaf: usize, siblings: &[usize], directions: &[usize], root: usize) {
+        let computed = self.merkle_root(perm, leaf, siblings, directions);
+        self.constrain(computed, LinComb::constant(F::ONE), LinComb::var(root));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Goldilocks;
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+
+    #[test]
+    fn dense_tree_paths_and_updates() {
+        let perm = Poseidon::goldilocks_t8();
+        let leaves: Vec<Goldilocks> = (0..11).map(|i| f(100 + i)).collect();
+        let mut tree = MerkleTree::new(perm.clone(), 4, &leaves);
+        for i in [0, 5, 10, 15] {
+            assert!(verify_path(&perm, tree.root(), tree.leaf(i), &tree.path(i)));
+        }
+        assert!(!verify_path(&perm, tree.root(), f(7), &tree.path(3)));
+
+        let old_root = tree.root();
+        tree.update(6, f(42));
+        assert_ne!(tree.root(), old_root);
+        assert_eq!(tree.root(), MerkleTree::new(perm.clone(), 4, &{
+            let mut l = leaves.clone();
+            l[6] = f(42);
+            l
+        }).root());
+        assert!(verify_path(&perm, tree.root(), f(42), &tree.path(6)));
+    }
+
+    #[test]
+    fn digest_tree_paths() {
+        use crate::poseidon::hash_n_to_digest;
+        let perm = Poseidon::goldilocks_t8();
+        let leaves: Vec<_> = (0..8).map(|i| hash_n_to_digest(&perm, &[f(i), f(2 * i)])).collect();
+        let tree = DigestTree::new(&perm, leaves.clone());
+        assert_eq!(tree.depth(), 3);
+        for i in 0..8 {
+            assert_eq!(tree.path(i).compute_root(&perm, leaves[i as usize]), tree.root());
+        }
+        assert_ne!(tree.path(2).compute_root(&perm, leaves[3]), tree.root());
+        // leaf hashing and node compression are domain-separated
+        let node = compress_digests(&perm, &leaves[0], &leaves[1]);
+        assert_ne!(node, hash_n_to_digest(&perm, &[leaves[0], leaves[1]].concat()));
+    }
+
+    #[test]
+    fn sparse_tree() {
+        let perm = Poseidon::goldilocks_t8();
+        let mut smt = SparseMerkleTree::new(perm.clone());
+        let empty_root = smt.root();
+        let (k1, k2) = (f(0xdead_beef_0000_0001), -f(5));
+        smt.insert(k1, f(1));
+        smt.insert(k2, f(2));
+        assert_eq!((smt.get(k1), smt.get(k2), smt.get(f(9))), (f(1), f(2), f(0)));
+        assert!(smt.verify(k2, f(2), &smt.path(k2)));
+        assert!(smt.verify(f(9), f(0), &smt.path(f(9))));
+        assert!(!smt.verify(k1, f(3), &smt.path(k1)));
+        smt.insert(k1, f(0));
+        smt.insert(k2, f(0));
+        assert_eq!(smt.root(), empty_root);
+    }
+
+    #[test]
+    fn membership_gadget() {
+        let perm = Poseidon::goldilocks_t8();
+        let leaves: Vec<Goldilocks> = (0..8).map(|i| f(i * i + 1)).collect();
+        let tree = MerkleTree::new(perm.clone(), 3, &leaves);
+        let path = tree.path(5);
+
+        let mut b = Builder::new();
+        let root = b.alloc_public(tree.root());
+        let leaf = b.alloc(tree.leaf(5));
+        let sibs: Vec<usize> = path.siblings.iter().map(|s| b.alloc(*s)).collect();
+        let dirs: Vec<usize> = path.directions().iter().map(|d| b.alloc(f(*d as u64))).collect();
+        b.merkle_membership(&perm, leaf, &sibs, &dirs, root);
+        let w = b.witness();
+        assert_eq!(crate::verify_with_public_inputs(&b, &[tree.root()], &w), Ok(()));
+        assert!(crate::verify_with_public_inputs(&b, &[tree.root() + f(1)], &w).is_err());
+
+        // a non-boolean direction is rejected even if it happened to hash correctly
+        let mut bad = w.clone();
+        bad.values.insert(dirs[0], f(2));
+        assert!(!crate::verify(&b, &bad));
+    }
+
+    #[test]
+    fn sparse_membership_gadget() {
+        let perm = Poseidon::goldilocks_t8();
+        let mut smt = SparseMerkleTree::with_depth(perm.clone(), 16);
+        smt.insert(f(0x1234), f(77));
+        smt.insert(f(0x0042), f(5));
+
+        let mut b = Builder::new();
+        let root = b.alloc_public(smt.root());
+        let leaf = b.alloc(f(77));
+        let sibs: Vec<usize> = smt.path(f(0x1234)).iter().map(|s| b.alloc(*s)).collect();
+        let dirs: Vec<usize> = smt.key_bits(f(0x1234)).iter().map(|d| b.alloc(f(*d as u64))).collect();
+        b.merkle_membership(&perm, leaf, &sibs, &dirs, root);
+        assert!(crate::verify(&b, &b.witness()));
+    }
+}
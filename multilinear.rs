@@ -0,0 +1,225 @@
+// multilinear.rs — multilinear polynomials in evaluation form and the sumcheck protocol.
+//
+// A polynomial in n variables is stored as its 2^n evaluations on the boolean hypercube,
+// with x_1 as the most significant bit of the index: evals[i] = f(bits of i, x_1 first).
+// Fixing x_1 = r therefore folds the two halves of the table.
+//
+// Sumcheck proves sum_{x ∈ {0,1}^n} g(f_1(x), .., f_k(x)) = claim for multilinear f_j and a
+// combining function g of total degree d (e.g. a product of the f_j). In round i the prover
+// sends s_i(t) as its values at t = 0..=d; the verifier checks s_i(0) + s_i(1) against the
+// running claim and replaces it with s_i(r_i). At the end the verifier is left with the
+// claim g(f_1(r), .., f_k(r)) at the random point r, to be checked against an oracle or a
+// commitment opening.
+
+use crate::field::PrimeField;
+use crate::transcript::Transcript;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultilinearPolynomial<F: PrimeField> {
+    pub num_vars: usize,
+    pub evals: Vec<F>,
+}
+
+impl<F: PrimeField> MultilinearPolynomial<F> {
+    pub fn new(evals: Vec<F>) -> Self {
+        assert!(evals.len().is_power_of_two(), "evaluation table length must be a power of two");
+        Self { num_vars: evals.len().trailing_zeros() as usize, evals }
+    }
+
+    // bind x_1 = r, leaving a polynomial in x_2..x_n
+    pub fn fix_variable(&self, r: F) -> Self {
+        let half = self.evals.len() / 2;
+        let (lo, hi) = self.evals.split_at(half);
+        Self::new(lo.iter().zip(hi).map(|(a, b)| *a + r * (*b - *a)).collect())
+    }
+
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars, "point has the wrong number of coordinates");
+        point.iter().fold(self.clone(), |p, r| p.fix_variable(*r)).evals[0]
+    }
+
+    // eq(r, x) = prod(r_i x_i + (1 - r_i)(1 - x_i)) for every x in the hypercube
+    pub fn eq(r: &[F]) -> Self {
+        let mut evals = vec![F::ONE];
+        for ri in r {
+            evals = evals.iter().flat_map(|e| [*e * (F::ONE - *ri), *e * *ri]).collect();
+        }
+        // flat_map appends x_i as the low bit, so x_1 ends up most significant
+        Self::new(evals)
+    }
+}
+
+pub fn eq_eval<F: PrimeField>(r: &[F], x: &[F]) -> F {
+    assert_eq!(r.len(), x.len());
+    r.iter().zip(x).fold(F::ONE, |acc, (a, b)| acc * (*a * *b + (F::ONE - *a) * (F::ONE - *b)))
+}
+
+// value at r of the degree-d polynomial with the given values at 0..=d
+pub fn interpolate_at<F: PrimeField>(evals: &[F], r: F) -> F {
+    let points: Vec<F> = (0..evals.len() as u64).map(F::from_u64).collect();
+    let mut acc = F::ZERO;
+    for (i, (xi, yi)) in points.iter().zip(evals).enumerate() {
+        let (mut num, mut den) = (F::ONE, F::ONE);
+        for (j, xj) in points.iter().enumerate() {
+            if i == j { continue; }
+            num *= r - *xj;
+            den *= *xi - *xj;
+        }
+        acc += *yi * num * den.inverse().unwrap();
+    }
+    acc
+}
+
+// ---- Sumcheck ----
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SumcheckProof<F: PrimeField> {
+    // round i: s_i(0), .., s_i(d)
+    pub round_polys: Vec<Vec<F>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SumcheckError {
+    RoundCount { expected: usize, got: usize },
+    // the round polynomial does not have degree + 1 evaluations
+    Degree { round: usize },
+    // s_i(0) + s_i(1) differs from the running claim
+    RoundSum { round: usize },
+}
+
+impl fmt::Display for SumcheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SumcheckError::RoundCount { expected, got } => write!(f, "expected {expected} sumcheck rounds, got {got}"),
+            SumcheckError::Degree { round } => write!(f, "round {round} polynomial has the wrong degree"),
+            SumcheckError::RoundSum { round } => write!(f, "round {round} polynomial does not match the claim"),
+        }
+    }
+}
+
+impl std::error::Error for SumcheckError {}
+
+// Returns the proof, the random point r and the final values f_j(r).
+pub fn sumcheck_prove<F: PrimeField>(
+    polys: &[MultilinearPolynomial<F>],
+    degree: usize,
+    combine: impl Fn(&[F]) -> F,
+    transcript: &mut Transcript,
+) -> (SumcheckProof<F>, Vec<F>, Vec<F>) {
+    assert!(!polys.is_empty(), "sumcheck needs at least one polynomial");
+    let num_vars = polys[0].num_vars;
+    assert!(polys.iter().all(|p| p.num_vars == num_vars), "all polynomials need the same number of variables");
+    let mut polys = polys.to_vec();
+    let mut round_polys = Vec::with_capacity(num_vars);
+    let mut point = Vec::with_capacity(num_vars);
+    for _ in 0..num_vars {
+        let half = polys[0].evals.len() / 2;
+        let mut s = vec![F::ZERO; degree + 1];
+        let mut vals = vec![F::ZERO; polys.len()];
+        for i in 0..half {
+            // each f_j restricted to (x_1 = t, rest = i) is linear in t
+            for (t, st) in s.iter_mut().enumerate() {
+                let t = F::from_u64(t as u64);
+                for (v, p) in vals.iter_mut().zip(&polys) {
+                    *v = p.evals[i] + t * (p.evals[i + half] - p.evals[i]);
+                }
+                *st += combine(&vals);
+            }
+        }
+        transcript.append_fields(b"sumcheck round", &s);
+        let r: F = transcript.challenge(b"sumcheck challenge");
+        polys = polys.iter().map(|p| p.fix_variable(r)).collect();
+        round_polys.push(s);
+        point.push(r);
+    }
+    let finals = polys.iter().map(|p| p.evals[0]).collect();
+    (SumcheckProof { round_polys }, point, finals)
+}
+
+// Checks the rounds and returns (r, expected g(f_1(r), .., f_k(r))).
+pub fn sumcheck_verify<F: PrimeField>(
+    claim: F,
+    num_vars: usize,
+    degree: usize,
+    proof: &SumcheckProof<F>,
+    transcript: &mut Transcript,
+) -> Result<(Vec<F>, F), SumcheckError> {
+    // s_i(0) + s_i(1) needs at least two evaluations per round
+    if degree < 1 { return Err(SumcheckError::Degree { round: 0 }); }
+    if proof.round_polys.len() != num_vars {
+        return Err(SumcheckError::RoundCount { expected: num_vars, got: proof.round_polys.len() });
+    }
+    let mut claim = claim;
+    let mut point = Vec::with_capacity(num_vars);
+    for (round, s) in proof.round_polys.iter().enumerate() {
+        if s.len() != degree + 1 { return Err(SumcheckError::Degree { round }); }
+        if s[0] + s[1] != claim { return Err(SumcheckError::RoundSum { round }); }
+        transcript.append_fields(b"sumcheck round", s);
+        let r: F = transcript.challenge(b"sumcheck challenge");
+        claim = interpolate_at(s, r);
+        point.push(r);
+    }
+    Ok((point, claim))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Goldilocks;
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+    fn mle(n: usize, seed: u64) -> MultilinearPolynomial<Goldilocks> {
+        MultilinearPolynomial::new((0..1u64 << n).map(|i| f(i * i * seed + 3 * seed + 1)).collect())
+    }
+
+    #[test]
+    fn evaluation_and_eq() {
+        let p = mle(3, 5);
+        // on the hypercube the MLE agrees with the table (x_1 most significant)
+        assert_eq!(p.evaluate(&[f(1), f(0), f(1)]), p.evals[0b101]);
+        // multilinearity in x_1
+        let (a, b, r) = (p.evaluate(&[f(0), f(7), f(9)]), p.evaluate(&[f(1), f(7), f(9)]), f(12345));
+        assert_eq!(p.evaluate(&[r, f(7), f(9)]), a + r * (b - a));
+
+        let r = [f(3), f(11), -f(2)];
+        let eq = MultilinearPolynomial::eq(&r);
+        assert_eq!(eq.evals.iter().fold(f(0), |acc, x| acc + *x), f(1));
+        assert_eq!(eq.evals[0b110], eq_eval(&r, &[f(1), f(1), f(0)]));
+        let x = [f(8), f(1000), f(4)];
+        assert_eq!(eq.evaluate(&x), eq_eval(&r, &x));
+        // sum_x eq(r, x) p(x) = p(r)
+        assert_eq!(eq.evals.iter().zip(&p.evals).fold(f(0), |acc, (e, v)| acc + *e * *v), p.evaluate(&r));
+    }
+
+    #[test]
+    fn sumcheck_products_of_mles() {
+        for k in 1..=3 {
+            let polys: Vec<_> = (0..k).map(|j| mle(4, j as u64 + 2)).collect();
+            let product = |vals: &[Goldilocks]| vals.iter().fold(f(1), |acc, v| acc * *v);
+            let claim = (0..16).fold(f(0), |acc, i| acc + product(&polys.iter().map(|p| p.evals[i]).collect::<Vec<_>>()));
+
+            let (proof, point, finals) = sumcheck_prove(&polys, k, product, &mut Transcript::new(b"sumcheck test"));
+            let (vpoint, expected) = sumcheck_verify(claim, 4, k, &proof, &mut Transcript::new(b"sumcheck test")).unwrap();
+            assert_eq!(vpoint, point);
+            assert_eq!(finals, polys.iter().map(|p| p.evaluate(&point)).collect::<Vec<_>>());
+            assert_eq!(expected, product(&finals));
+
+            // a wrong claim is caught in the first round
+            assert_eq!(sumcheck_verify(claim + f(1), 4, k, &proof, &mut Transcript::new(b"sumcheck test")),
+                       Err(SumcheckError::RoundSum { round: 0 }));
+            // a tampered round polynomial either fails a later round or the final check
+            let mut bad = proof.clone();
+            bad.round_polys[2][0] += f(1);
+            bad.round_polys[2][1] -= f(1);
+            match sumcheck_verify(claim, 4, k, &bad, &mut Transcript::new(b"sumcheck test")) {
+                Ok((_, e)) => assert_ne!(e, expected),
+                Err(e) => assert_eq!(e, SumcheckError::RoundSum { round: 3 }),
+            }
+        }
+
+        // degree 0 is rejected before any round is read
+        let proof = SumcheckProof { round_polys: vec![vec![f(0)]] };
+        assert_eq!(sumcheck_verify(f(0), 1, 0, &proof, &mut Transcript::new(b"sumcheck test")),
+                   Err(SumcheckError::Degree { round: 0 }));
+    }
+}
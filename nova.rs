@@ -0,0 +1,555 @@
+// nova.rs — Nova-style folding (Kothapalli–Setty–Tzialla 2022) for iterated `Builder`
+// circuits over BN254, with Pedersen commitments in G1.
+//
+// Relaxed R1CS: z is the dense assignment indexed by builder variable with z[ONE] = u, so
+// constant terms scale with u, and every constraint satisfies
+//   (A·z)(B·z) = u·(C·z) + E_i.
+// A plain satisfying assignment is the case u = 1, E = 0. An instance carries Pedersen
+// commitments to the witness part W (every variable except ONE and the public inputs,
+// in index order) and to E; the public part x is sent in the clear.
+//
+// Folding (the NIFS): with cross term T_i = a1·b2 + a2·b1 - u1·c2 - u2·c1 and a transcript
+// challenge r, the folded pair is W = W1 + r·W2, E = E1 + r·T + r²·E2, u = u1 + r·u2,
+// x = x1 + r·x2, and the verifier derives the folded commitments from comm_T alone.
+//
+// Folding accumulator: each step is a `StepCircuit` wrapped so its public inputs are
+// z_i || z_{i+1}. The prover folds every step into one running instance, starting from the
+// all-zero relaxed instance (which is trivially satisfied). This is NOT incremental
+// verifiable computation: there is no augmented circuit verifying the previous fold
+// in-circuit (that needs a curve cycle), so the proof carries one `FoldStep` per step and
+// the verifier re-folds them itself — proof size and verifier time are O(steps) group
+// operations. What it saves over a monolithic circuit is the prover's memory and the
+// verifier's per-step R1CS checks: only the final folded instance is checked.
+//
+// The final check is compressed: the prover does not reveal (W, E) but proves the folded
+// relaxed instance with the Spartan backend (`spartan::prove_relaxed`). The Pedersen key
+// doubles as its multilinear PCS — p(r) = <evals, eq(r)> is opened with the Bulletproofs
+// inner-product argument — so the decider opens comm_W and comm_E themselves, in
+// O(log n) group elements. Commitments are not blinded, so proofs are not zero-knowledge.
+
+use crate::bn254::{msm, Fq, Fr, G1};
+use crate::field::{limbs_to_field, PrimeField};
+use crate::keccak::shake256;
+use crate::multilinear::MultilinearPolynomial;
+use crate::pcs::MultilinearPcs;
+use crate::spartan::{self, R1cs, RelaxedSpartanProof, SpartanError};
+use crate::transcript::Transcript;
+use crate::{try_verify, Builder, Constraint, LinComb, VerifyError};
+use std::fmt;
+
+// ---- Relaxed R1CS ----
+impl<F: PrimeField> Constraint<F> {
+    // (A·z, B·z, C·z) with constant terms read as multiples of z[ONE]
+    pub fn eval_homogeneous(&self, z: &[F]) -> [F; 3] {
+        [&self.a, &self.b, &self.c].map(|lc| {
+            lc.terms.iter().fold(lc.const_term * z[Builder::<F>::ONE], |acc, (v, c)| acc + *c * z[*v])
+        })
+    }
+
+    // E_i for the relaxed assignment (z, u): (A·z)(B·z) - u·(C·z)
+    pub fn relaxed_error(&self, z: &[F], u: F) -> F {
+        let [a, b, c] = self.eval_homogeneous(z);
+        a * b - u * c
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RelaxedR1cs<F: PrimeField> {
+    pub constraints: Vec<Constraint<F>>,
+    pub num_vars: usize,
+    pub public_inputs: Vec<usize>,
+    // committed variables, in index order
+    pub witness_vars: Vec<usize>,
+}
+
+impl<F: PrimeField> RelaxedR1cs<F> {
+    pub fn new(builder: &Builder<F>) -> Self {
+        let witness_vars = (1..builder.next_var).filter(|v| !builder.is_public(*v)).collect();
+        Self {
+            constraints: builder.constraints.clone(),
+            num_vars: builder.next_var,
+            public_inputs: builder.public_inputs.clone(),
+            witness_vars,
+        }
+    }
+
+    // dense z from (W, u, x)
+    pub fn assignment(&self, w: &[F], u: F, x: &[F]) -> Vec<F> {
+        let mut z = vec![F::ZERO; self.num_vars];
+        z[Builder::<F>::ONE] = u;
+        for (v, val) in self.witness_vars.iter().zip(w).chain(self.public_inputs.iter().zip(x)) {
+            z[*v] = *val;
+        }
+        z
+    }
+
+    // T for folding (z1, u1) with (z2, u2)
+    pub fn cross_term(&self, z1: &[F], u1: F, z2: &[F], u2: F) -> Vec<F> {
+        self.constraints.iter().map(|con| {
+            let ([a1, b1, c1], [a2, b2, c2]) = (con.eval_homogeneous(z1), con.eval_homogeneous(z2));
+            a1 * b2 + a2 * b1 - u1 * c2 - u2 * c1
+        }).collect()
+    }
+
+    // index of the first constraint whose relaxed error differs from e
+    pub fn first_unsatisfied(&self, z: &[F], u: F, e: &[F]) -> Option<usize> {
+        self.constraints.iter().zip(e).position(|(con, e)| con.relaxed_error(z, u) != *e)
+    }
+}
+
+// ---- Pedersen vector commitments ----
+#[derive(Clone, Debug)]
+pub struct PedersenKey {
+    pub generators: Vec<G1>,
+    // independent base for the inner-product argument
+    pub ipa_base: G1,
+}
+
+impl PedersenKey {
+    // nothing-up-my-sleeve generators: x = SHAKE256(label || i || counter) mod p, first
+    // counter for which x^3 + 3 is a square (G1 has cofactor 1); index n is the IPA base
+    pub fn new(label: &[u8], n: usize) -> Self {
+        let mut generators: Vec<G1> = (0..=n as u64).map(|i| {
+            (0u64..).find_map(|ctr| {
+                let mut input = label.to_vec();
+                input.extend(i.to_le_bytes());
+                input.extend(ctr.to_le_bytes());
+                let limbs: Vec<u64> = shake256(&input, 48).chunks(8)
+                    .map(|c| u64::from_le_bytes(c.try_into().unwrap())).collect();
+                let x: Fq = limbs_to_field(&limbs);
+                let y = (x * x * x + Fq::from_u64(3)).sqrt()?;
+                G1::from_affine(x, y)
+            }).unwrap()
+        }).collect();
+        let ipa_base = generators.pop().unwrap();
+        Self { generators, ipa_base }
+    }
+
+    pub fn commit(&self, values: &[Fr]) -> G1 {
+        assert!(values.len() <= self.generators.len(), "commitment key too short");
+        msm(&self.generators[..values.len()], values)
+    }
+}
+
+// ---- Inner-product openings ----
+// As a multilinear PCS, commit(p) = <evals, G> and p(r) = <evals, eq(r)> is shown with the
+// Bulletproofs argument: each round halves (a, b, G) with a challenge x and sends
+// L = <a_lo, G_hi> + <a_lo, b_hi>·U, R = <a_hi, G_lo> + <a_hi, b_lo>·U, so that
+// C + v·U + Σ x_j²·L_j + x_j⁻²·R_j = a·(<s, G> + <s, b>·U) for the final scalar a, where
+// s_i is the product of x_j or x_j⁻¹ by the bits of i. U is ipa_base scaled by a challenge.
+#[derive(Clone, Debug)]
+pub struct IpaProof {
+    pub l: Vec<G1>,
+    pub r: Vec<G1>,
+    pub a: Fr,
+}
+
+fn inner_product(a: &[Fr], b: &[Fr]) -> Fr { a.iter().zip(b).fold(Fr::ZERO, |acc, (x, y)| acc + *x * *y) }
+
+fn ipa_challenge(t: &mut Transcript, l: &G1, r: &G1) -> Fr {
+    t.append_bytes(b"ipa round", &[l.to_eth_bytes(), r.to_eth_bytes()].concat());
+    t.challenge(b"ipa challenge")
+}
+
+impl MultilinearPcs<Fr> for PedersenKey {
+    type Commitment = G1;
+    type Opening = IpaProof;
+
+    fn commit(&self, poly: &MultilinearPolynomial<Fr>) -> G1 { PedersenKey::commit(self, &poly.evals) }
+    fn append_commitment(&self, commitment: &G1, transcript: &mut Transcript) {
+        transcript.append_bytes(b"pedersen commitment", &commitment.to_eth_bytes());
+    }
+
+    fn open(&self, poly: &MultilinearPolynomial<Fr>, point: &[Fr], transcript: &mut Transcript) -> IpaProof {
+        let (mut a, mut b) = (poly.evals.clone(), MultilinearPolynomial::eq(point).evals);
+        let mut g = self.generators[..a.len()].to_vec();
+        let base = self.ipa_base.mul(&transcript.challenge(b"ipa base"));
+        let (mut l, mut r) = (vec![], vec![]);
+        while a.len() > 1 {
+            let h = a.len() / 2;
+            let lj = msm(&g[h..], &a[..h]) + base.mul(&inner_product(&a[..h], &b[h..]));
+            let rj = msm(&g[..h], &a[h..]) + base.mul(&inner_product(&a[h..], &b[..h]));
+            let x = ipa_challenge(transcript, &lj, &rj);
+            let x_inv = x.inverse().unwrap();
+            a = (0..h).map(|i| a[i] * x + a[h + i] * x_inv).collect();
+            b = (0..h).map(|i| b[i] * x_inv + b[h + i] * x).collect();
+            g = (0..h).map(|i| g[i].mul(&x_inv) + g[h + i].mul(&x)).collect();
+            l.push(lj);
+            r.push(rj);
+        }
+        IpaProof { l, r, a: a[0] }
+    }
+
+    fn verify(&self, commitment: &G1, point: &[Fr], value: Fr, opening: &IpaProof, transcript: &mut Transcript) -> bool {
+        let k = point.len();
+        if opening.l.len() != k || opening.r.len() != k || self.generators.len() < 1 << k { return false; }
+        let base = self.ipa_base.mul(&transcript.challenge(b"ipa base"));
+        let xs: Vec<Fr> = opening.l.iter().zip(&opening.r).map(|(l, r)| ipa_challenge(transcript, l, r)).collect();
+        let Some(x_invs) = xs.iter().map(|x| x.inverse()).collect::<Option<Vec<Fr>>>() else { return false };
+        // round j splits on bit k - 1 - j of the index; the high half is scaled by x_j
+        let s: Vec<Fr> = (0..1usize << k)
+            .map(|i| (0..k).fold(Fr::ONE, |acc, j| acc * if (i >> (k - 1 - j)) & 1 == 1 { xs[j] } else { x_invs[j] }))
+            .collect();
+        let b = inner_product(&s, &MultilinearPolynomial::eq(point).evals);
+        let squares = |v: &[Fr]| -> Vec<Fr> { v.iter().map(|x| *x * *x).collect() };
+        let lhs = *commitment + base.mul(&value) + msm(&opening.l, &squares(&xs)) + msm(&opening.r, &squares(&x_invs));
+        let a_s: Vec<Fr> = s.iter().map(|s| *s * opening.a).collect();
+        lhs == msm(&self.generators[..1 << k], &a_s) + base.mul(&(opening.a * b))
+    }
+}
+
+// ---- Folding ----
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelaxedInstance {
+    pub comm_w: G1,
+    pub comm_e: G1,
+    pub u: Fr,
+    pub x: Vec<Fr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelaxedWitness {
+    pub w: Vec<Fr>,
+    pub e: Vec<Fr>,
+}
+
+impl RelaxedInstance {
+    // the trivially satisfied instance folding starts from
+    pub fn zero(shape: &RelaxedR1cs<Fr>) -> Self {
+        Self { comm_w: G1::identity(), comm_e: G1::identity(), u: Fr::ZERO, x: vec![Fr::ZERO; shape.public_inputs.len()] }
+    }
+    // a plain R1CS instance: u = 1, E = 0
+    pub fn strict(comm_w: G1, x: Vec<Fr>) -> Self { Self { comm_w, comm_e: G1::identity(), u: Fr::ONE, x } }
+
+    fn append_to(&self, t: &mut Transcript, label: &[u8]) {
+        t.append_bytes(label, &[self.comm_w.to_eth_bytes(), self.comm_e.to_eth_bytes()].concat());
+        t.append_field(label, &self.u);
+        t.append_fields(label, &self.x);
+    }
+}
+
+impl RelaxedWitness {
+    pub fn zero(shape: &RelaxedR1cs<Fr>) -> Self {
+        Self { w: vec![Fr::ZERO; shape.witness_vars.len()], e: vec![Fr::ZERO; shape.constraints.len()] }
+    }
+}
+
+fn fold_challenge(t: &mut Transcript, u1: &RelaxedInstance, u2: &RelaxedInstance, comm_t: &G1) -> Fr {
+    u1.append_to(t, b"running instance");
+    u2.append_to(t, b"step instance");
+    t.append_bytes(b"cross term", &comm_t.to_eth_bytes());
+    t.challenge(b"fold")
+}
+
+fn fold_vec(a: &[Fr], b: &[Fr], r: Fr) -> Vec<Fr> { a.iter().zip(b).map(|(x, y)| *x + r * *y).collect() }
+
+fn fold_instance(u1: &RelaxedInstance, u2: &RelaxedInstance, comm_t: &G1, r: Fr) -> RelaxedInstance {
+    RelaxedInstance {
+        comm_w: u1.comm_w + u2.comm_w.mul(&r),
+        comm_e: u1.comm_e + comm_t.mul(&r) + u2.comm_e.mul(&(r * r)),
+        u: u1.u + r * u2.u,
+        x: fold_vec(&u1.x, &u2.x, r),
+    }
+}
+
+// fold (u2, w2) into (u1, w1); returns the folded pair and comm_T
+pub fn nifs_prove(
+    key: &PedersenKey, shape: &RelaxedR1cs<Fr>, t: &mut Transcript,
+    (u1, w1): (&RelaxedInstance, &RelaxedWitness), (u2, w2): (&RelaxedInstance, &RelaxedWitness),
+) -> (RelaxedInstance, RelaxedWitness, G1) {
+    let z1 = shape.assignment(&w1.w, u1.u, &u1.x);
+    let z2 = shape.assignment(&w2.w, u2.u, &u2.x);
+    let cross = shape.cross_term(&z1, u1.u, &z2, u2.u);
+    let comm_t = key.commit(&cross);
+    let r = fold_challenge(t, u1, u2, &comm_t);
+    let e = w1.e.iter().zip(&cross).zip(&w2.e).map(|((e1, t), e2)| *e1 + r * *t + r * r * *e2).collect();
+    let w = RelaxedWitness { w: fold_vec(&w1.w, &w2.w, r), e };
+    (fold_instance(u1, u2, &comm_t, r), w, comm_t)
+}
+
+pub fn nifs_verify(t: &mut Transcript, u1: &RelaxedInstance, u2: &RelaxedInstance, comm_t: &G1) -> RelaxedInstance {
+    let r = fold_challenge(t, u1, u2, comm_t);
+    fold_instance(u1, u2, comm_t, r)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NovaError {
+    // a step's assignment does not satisfy the step circuit
+    Witness(VerifyError),
+    Arity { expected: usize, got: usize },
+    // the decider's witness does not open the running commitments
+    Commitment,
+    Unsatisfied(usize),
+    // the compressed decider proof is invalid
+    Decider(SpartanError),
+}
+
+impl fmt::Display for NovaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NovaError::Witness(e) => write!(f, "step witness: {e}"),
+            NovaError::Arity { expected, got } => write!(f, "expected a state of {expected} elements, got {got}"),
+            NovaError::Commitment => write!(f, "witness does not match the instance commitments"),
+            NovaError::Unsatisfied(i) => write!(f, "relaxed constraint {i} is not satisfied"),
+            NovaError::Decider(e) => write!(f, "compressed decider: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for NovaError {}
+
+// the uncompressed check: (W, E) opens the commitments and satisfies the relaxed R1CS
+pub fn decide(key: &PedersenKey, shape: &RelaxedR1cs<Fr>, inst: &RelaxedInstance, wit: &RelaxedWitness) -> Result<(), NovaError> {
+    if key.commit(&wit.w) != inst.comm_w || key.commit(&wit.e) != inst.comm_e {
+        return Err(NovaError::Commitment);
+    }
+    let z = shape.assignment(&wit.w, inst.u, &inst.x);
+    shape.first_unsatisfied(&z, inst.u, &wit.e).map_or(Ok(()), |i| Err(NovaError::Unsatisfied(i)))
+}
+
+// ---- Folding accumulator ----
+// One step of an iterated computation z_{i+1} = F(z_i).
+pub trait StepCircuit<F: PrimeField> {
+    fn arity(&self) -> usize;
+    // `z_in` are the input variables; returns the outputs (`arity` of them)
+    fn synthesize(&self, b: &mut Builder<F>, z_in: &[usize]) -> Vec<LinComb<F>>;
+}
+
+// public inputs z_in || z_out; values are None in setup mode
+fn step_builder<F: PrimeField, C: StepCircuit<F>>(circuit: &C, z_in: Option<&[F]>) -> Builder<F> {
+    let mut b = if z_in.is_some() { Builder::new() } else { Builder::setup() };
+    let inputs: Vec<usize> = (0..circuit.arity()).map(|i| b.alloc_public_opt(z_in.map(|z| z[i]))).collect();
+    let outputs = circuit.synthesize(&mut b, &inputs);
+    assert_eq!(outputs.len(), circuit.arity(), "step circuit must return `arity` outputs");
+    for lc in outputs {
+        let out = b.alloc_public_opt(b.eval_lc(&lc));
+        b.constrain(lc, LinComb::constant(F::ONE), LinComb::var(out));
+    }
+    b
+}
+
+#[derive(Clone, Debug)]
+pub struct FoldingParams {
+    pub shape: RelaxedR1cs<Fr>,
+    // the same circuit in Spartan's layout, for the decider (W is its witness half)
+    pub r1cs: R1cs<Fr>,
+    pub key: PedersenKey,
+}
+
+impl FoldingParams {
+    pub fn new<C: StepCircuit<Fr>>(circuit: &C) -> Self {
+        let builder = step_builder::<Fr, C>(circuit, None);
+        let (shape, r1cs) = (RelaxedR1cs::new(&builder), R1cs::new(&builder));
+        // long enough for the zero-padded W and E tables the decider opens
+        let key = PedersenKey::new(b"delta_zk nova pedersen", (1 << (r1cs.log_cols - 1)).max(1 << r1cs.log_rows));
+        Self { shape, r1cs, key }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FoldStep {
+    pub comm_w: G1,
+    pub comm_t: G1,
+    // z_{i+1}
+    pub output: Vec<Fr>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AccumulationProof {
+    pub steps: Vec<FoldStep>,
+    // Spartan proof of the final running instance
+    pub decider: RelaxedSpartanProof<Fr, PedersenKey>,
+}
+
+fn accumulator_transcript(z0: &[Fr]) -> Transcript {
+    let mut t = Transcript::new(b"delta_zk nova accumulator");
+    t.append_fields(b"z0", z0);
+    t
+}
+
+// Prover state: the running relaxed pair and the current z_i.
+pub struct FoldingAccumulator<'a> {
+    params: &'a FoldingParams,
+    transcript: Transcript,
+    running: (RelaxedInstance, RelaxedWitness),
+    z: Vec<Fr>,
+    steps: Vec<FoldStep>,
+}
+
+impl<'a> FoldingAccumulator<'a> {
+    pub fn new(params: &'a FoldingParams, z0: &[Fr]) -> Self {
+        let running = (RelaxedInstance::zero(&params.shape), RelaxedWitness::zero(&params.shape));
+        Self { params, transcript: accumulator_transcript(z0), running, z: z0.to_vec(), steps: vec![] }
+    }
+
+    pub fn state(&self) -> &[Fr] { &self.z }
+
+    pub fn prove_step<C: StepCircuit<Fr>>(&mut self, circuit: &C) -> Result<(), NovaError> {
+        if self.z.len() != circuit.arity() {
+            return Err(NovaError::Arity { expected: circuit.arity(), got: self.z.len() });
+        }
+        let b = step_builder(circuit, Some(&self.z));
+        try_verify(&b, &b.witness()).map_err(NovaError::Witness)?;
+        // W and x cover every variable, including ones no constraint reads
+        let missing: Vec<usize> = (1..b.next_var).filter(|v| b.value(*v).is_none()).collect();
+        if !missing.is_empty() {
+            return Err(NovaError::Witness(VerifyError::Unassigned(missing)));
+        }
+        let (shape, key) = (&self.params.shape, &self.params.key);
+        let w: Vec<Fr> = shape.witness_vars.iter().map(|v| b.value(*v).unwrap()).collect();
+        let x = b.public_values().unwrap();
+        let step = (RelaxedInstance::strict(key.commit(&w), x.clone()), RelaxedWitness { w, e: vec![Fr::ZERO; shape.constraints.len()] });
+        let (u, w, comm_t) = nifs_prove(key, shape, &mut self.transcript, (&self.running.0, &self.running.1), (&step.0, &step.1));
+        self.running = (u, w);
+        self.z = x[circuit.arity()..].to_vec();
+        self.steps.push(FoldStep { comm_w: step.0.comm_w, comm_t, output: self.z.clone() });
+        Ok(())
+    }
+
+    // compress the running instance; folding keeps it satisfied, so an error here is a bug
+    pub fn finish(self) -> Result<AccumulationProof, NovaError> {
+        let (inst, wit) = &self.running;
+        let decider = spartan::prove_relaxed(&self.params.key, &self.params.r1cs, &wit.w, inst.u, &inst.x, &wit.e)
+            .map_err(NovaError::Decider)?;
+        Ok(AccumulationProof { steps: self.steps, decider })
+    }
+}
+
+// Re-fold the step instances and verify the compressed decider; returns the final state z_n.
+pub fn verify_accumulation(params: &FoldingParams, z0: &[Fr], proof: &AccumulationProof) -> Result<Vec<Fr>, NovaError> {
+    let arity = params.shape.public_inputs.len() / 2;
+    if z0.len() != arity {
+        return Err(NovaError::Arity { expected: arity, got: z0.len() });
+    }
+    let mut t = accumulator_transcript(z0);
+    let mut running = RelaxedInstance::zero(&params.shape);
+    let mut z = z0.to_vec();
+    for step in &proof.steps {
+        if step.output.len() != arity {
+            return Err(NovaError::Arity { expected: arity, got: step.output.len() });
+        }
+        let inst = RelaxedInstance::strict(step.comm_w, [z.as_slice(), &step.output].concat());
+        running = nifs_verify(&mut t, &running, &inst, &step.comm_t);
+        z = step.output.clone();
+    }
+    spartan::verify_relaxed(&params.key, &params.r1cs, &running.comm_w, &running.comm_e, running.u, &running.x, &proof.decider)
+        .map_err(NovaError::Decider)?;
+    Ok(z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poseidon_round;
+
+    fn f(x: u64) -> Fr { Fr::from_u64(x) }
+
+    // one toy Poseidon round per step
+    struct RoundStep;
+
+    impl StepCircuit<Fr> for RoundStep {
+        fn arity(&self) -> usize { 3 }
+        fn synthesize(&self, b: &mut Builder<Fr>, z_in: &[usize]) -> Vec<LinComb<Fr>> {
+            let state = [z_in[0], z_in[1], z_in[2]].map(LinComb::var);
+            b.poseidon_round_gadget(&state).to_vec()
+        }
+    }
+
+    #[test]
+    fn folding_preserves_satisfiability() {
+        // x * y = out + 2
+        let circuit = |x: u64, y: u64| {
+            let mut b = Builder::new();
+            let (xv, yv) = (b.alloc(f(x)), b.alloc(f(y)));
+            let out = b.alloc_public(f(x * y - 2));
+            b.constrain(LinComb::var(xv), LinComb::var(yv), LinComb::var(out).c(f(2)));
+            b
+        };
+        let shape = RelaxedR1cs::new(&circuit(1, 2));
+        let key = PedersenKey::new(b"test", 2);
+        let pair = |b: &Builder<Fr>| {
+            let w: Vec<Fr> = shape.witness_vars.iter().map(|v| b.value(*v).unwrap()).collect();
+            (RelaxedInstance::strict(key.commit(&w), b.public_values().unwrap()), RelaxedWitness { w, e: vec![Fr::ZERO] })
+        };
+        let (u1, w1) = pair(&circuit(3, 5));
+        let (u2, w2) = pair(&circuit(7, 11));
+        assert_eq!(decide(&key, &shape, &u1, &w1), Ok(()));
+
+        let (mut tp, mut tv) = (Transcript::new(b"nifs"), Transcript::new(b"nifs"));
+        let (u, w, comm_t) = nifs_prove(&key, &shape, &mut tp, (&u1, &w1), (&u2, &w2));
+        assert_eq!(nifs_verify(&mut tv, &u1, &u2, &comm_t), u);
+        assert_eq!(decide(&key, &shape, &u, &w), Ok(()));
+        assert!(!w.e[0].is_zero() && u.u != Fr::ONE);
+
+        // folding in an unsatisfying pair leaves an unsatisfied instance
+        let (bad_u, mut bad_w) = pair(&circuit(2, 2));
+        bad_w.w[0] = f(3);
+        let bad_u = RelaxedInstance { comm_w: key.commit(&bad_w.w), ..bad_u };
+        let (u, w, _) = nifs_prove(&key, &shape, &mut Transcript::new(b"nifs"), (&u1, &w1), (&bad_u, &bad_w));
+        assert_eq!(decide(&key, &shape, &u, &w), Err(NovaError::Unsatisfied(0)));
+    }
+
+    #[test]
+    fn inner_product_openings() {
+        let key = PedersenKey::new(b"test", 8);
+        let poly = MultilinearPolynomial::new((0..8).map(|i| f(i * i + 1)).collect());
+        let com = MultilinearPcs::commit(&key, &poly);
+        assert_eq!(com, key.commit(&poly.evals));
+        let point = [f(5), f(6), f(7)];
+        let value = poly.evaluate(&point);
+        let opening = key.open(&poly, &point, &mut Transcript::new(b"ipa"));
+        assert!(key.verify(&com, &point, value, &opening, &mut Transcript::new(b"ipa")));
+        assert!(!key.verify(&com, &point, value + Fr::ONE, &opening, &mut Transcript::new(b"ipa")));
+        assert!(!key.verify(&com.double(), &point, value, &opening, &mut Transcript::new(b"ipa")));
+        let mut bad = opening.clone();
+        bad.a += Fr::ONE;
+        assert!(!key.verify(&com, &point, value, &bad, &mut Transcript::new(b"ipa")));
+    }
+
+    #[test]
+    fn iterated_poseidon_round() {
+        let params = FoldingParams::new(&RoundStep);
+        let z0 = [f(1), f(2), f(3)];
+        let mut acc = FoldingAccumulator::new(&params, &z0);
+        for _ in 0..6 {
+            acc.prove_step(&RoundStep).unwrap();
+        }
+        let mut expected = z0;
+        for _ in 0..6 {
+            poseidon_round(&mut expected);
+        }
+        assert_eq!(acc.state(), expected);
+        let proof = acc.finish().unwrap();
+        assert_eq!(verify_accumulation(&params, &z0, &proof), Ok(expected.to_vec()));
+
+        assert!(verify_accumulation(&params, &[f(1), f(2), f(4)], &proof).is_err());
+        let mut bad = proof.clone();
+        bad.steps[2].output[0] += Fr::ONE;
+        assert!(verify_accumulation(&params, &z0, &bad).is_err());
+        let mut bad = proof.clone();
+        bad.steps[4].comm_t = bad.steps[4].comm_t.double();
+        assert!(matches!(verify_accumulation(&params, &z0, &bad), Err(NovaError::Decider(_))));
+        let mut bad = proof.clone();
+        bad.decider.witness_eval += Fr::ONE;
+        assert_eq!(verify_accumulation(&params, &z0, &bad), Err(NovaError::Decider(SpartanError::InnerClaim)));
+        assert!(matches!(verify_accumulation(&params, &z0[..2], &proof), Err(NovaError::Arity { .. })));
+    }
+
+    // RoundStep plus a variable that is never assigned or constrained
+    struct SloppyStep;
+
+    impl StepCircuit<Fr> for SloppyStep {
+        fn arity(&self) -> usize { 3 }
+        fn synthesize(&self, b: &mut Builder<Fr>, z_in: &[usize]) -> Vec<LinComb<Fr>> {
+            b.alloc_opt(None);
+            RoundStep.synthesize(b, z_in)
+        }
+    }
+
+    #[test]
+    fn unassigned_step_variable_is_an_error() {
+        let params = FoldingParams::new(&SloppyStep);
+        let mut acc = FoldingAccumulator::new(&params, &[f(1), f(2), f(3)]);
+        assert_eq!(acc.prove_step(&SloppyStep), Err(NovaError::Witness(VerifyError::Unassigned(vec![4]))));
+    }
+}
@@ -0,0 +1,163 @@
+// ntt.rs — radix-2 number-theoretic transforms over any `PrimeField` with enough two-adicity
+// (Goldilocks supports sizes up to 2^32).
+//
+// `ntt` maps the coefficients of a polynomial of degree < n to its evaluations on the
+// subgroup <ω_n> = {1, ω, .., ω^(n-1)}, both in natural order; ω_n is derived from the
+// field's multiplicative generator via `two_adic_root_of_unity`. The coset variants
+// evaluate on shift·<ω_n>, which is what a low-degree extension needs to stay disjoint
+// from the trace domain.
+
+use crate::field::PrimeField;
+
+// primitive 2^log_n-th root of unity
+pub fn root_of_unity<F: PrimeField>(log_n: u32) -> F {
+    assert!(log_n <= F::TWO_ADICITY, "field has no 2^{log_n}-th roots of unity");
+    (log_n..F::TWO_ADICITY).fold(F::two_adic_root_of_unity(), |w, _| w.square())
+}
+
+fn log2_exact(n: usize) -> u32 {
+    assert!(n.is_power_of_two(), "transform size {n} is not a power of two");
+    n.trailing_zeros()
+}
+
+pub fn bit_reverse_permute<T>(a: &mut [T]) {
+    let n = a.len();
+    if n <= 1 { return; }
+    let bits = log2_exact(n);
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j { a.swap(i, j); }
+    }
+}
+
+// in-place iterative Cooley–Tukey with root w (of order a.len())
+fn transform<F: PrimeField>(a: &mut [F], w: F) {
+    let n = a.len();
+    bit_reverse_permute(a);
+    let mut len = 2;
+    while len <= n {
+        let w_len = w.pow((n / len) as u64);
+        let half = len / 2;
+        let twiddles: Vec<F> = std::iter::successors(Some(F::ONE), |t| Some(*t * w_len)).take(half).collect();
+        for chunk in a.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(half);
+            for ((x, y), t) in lo.iter_mut().zip(hi.iter_mut()).zip(&twiddles) {
+                let v = *y * *t;
+                *y = *x - v;
+                *x += v;
+            }
+        }
+        len *= 2;
+    }
+}
+
+// coefficients -> evaluations on <ω_n>
+pub fn ntt<F: PrimeField>(a: &mut [F]) {
+    let w = root_of_unity(log2_exact(a.len()));
+    transform(a, w);
+}
+
+// evaluations on <ω_n> -> coefficients
+pub fn intt<F: PrimeField>(a: &mut [F]) {
+    let w = root_of_unity::<F>(log2_exact(a.len())).inverse().unwrap();
+    transform(a, w);
+    let n_inv = F::from_u64(a.len() as u64).inverse().unwrap();
+    for x in a.iter_mut() { *x *= n_inv; }
+}
+
+// coefficients -> evaluations on shift·<ω_n>
+pub fn coset_ntt<F: PrimeField>(a: &mut [F], shift: F) {
+    scale_by_powers(a, shift);
+    ntt(a);
+}
+
+// evaluations on shift·<ω_n> -> coefficients
+pub fn coset_intt<F: PrimeField>(a: &mut [F], shift: F) {
+    intt(a);
+    scale_by_powers(a, shift.inverse().expect("coset shift must be non-zero"));
+}
+
+// a[i] *= s^i
+fn scale_by_powers<F: PrimeField>(a: &mut [F], s: F) {
+    let mut p = F::ONE;
+    for x in a.iter_mut() {
+        *x *= p;
+        p *= s;
+    }
+}
+
+// Low-degree extension: given the evaluations of a polynomial of degree < n on <ω_n>,
+// return its evaluations on shift·<ω_{n·blowup}>.
+pub fn lde<F: PrimeField>(evals: &[F], blowup: usize, shift: F) -> Vec<F> {
+    let mut coeffs = evals.to_vec();
+    intt(&mut coeffs);
+    lde_from_coeffs(&coeffs, blowup, shift)
+}
+
+// as `lde`, starting from coefficients
+pub fn lde_from_coeffs<F: PrimeField>(coeffs: &[F], blowup: usize, shift: F) -> Vec<F> {
+    assert!(blowup.is_power_of_two(), "blowup {blowup} is not a power of two");
+    let mut out = coeffs.to_vec();
+    out.resize(coeffs.len().next_power_of_two() * blowup, F::ZERO);
+    coset_ntt(&mut out, shift);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Goldilocks;
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+
+    fn naive_dft(coeffs: &[Goldilocks], points: impl Iterator<Item = Goldilocks>) -> Vec<Goldilocks> {
+        points.map(|x| coeffs.iter().rev().fold(f(0), |acc, c| acc * x + *c)).collect()
+    }
+
+    #[test]
+    fn roots_of_unity() {
+        let w = root_of_unity::<Goldilocks>(32);
+        assert_eq!(w.pow(1 << 31), -f(1));
+        assert_eq!(root_of_unity::<Goldilocks>(1), -f(1));
+        assert_eq!(root_of_unity::<Goldilocks>(0), f(1));
+        let mut a: Vec<u32> = (0..8).collect();
+        bit_reverse_permute(&mut a);
+        assert_eq!(a, [0, 4, 2, 6, 1, 5, 3, 7]);
+    }
+
+    #[test]
+    fn ntt_matches_naive_dft() {
+        for log_n in 0..7 {
+            let n = 1 << log_n;
+            let coeffs: Vec<Goldilocks> = (0..n as u64).map(|i| f(i * i * 31 + 7) - f(1000)).collect();
+            let w = root_of_unity::<Goldilocks>(log_n);
+            let mut a = coeffs.clone();
+            ntt(&mut a);
+            assert_eq!(a, naive_dft(&coeffs, (0..n as u64).map(|i| w.pow(i))));
+            intt(&mut a);
+            assert_eq!(a, coeffs);
+
+            let shift = Goldilocks::multiplicative_generator();
+            let mut c = coeffs.clone();
+            coset_ntt(&mut c, shift);
+            assert_eq!(c, naive_dft(&coeffs, (0..n as u64).map(|i| shift * w.pow(i))));
+            coset_intt(&mut c, shift);
+            assert_eq!(c, coeffs);
+        }
+    }
+
+    #[test]
+    fn low_degree_extension() {
+        let coeffs: Vec<Goldilocks> = (1..=8).map(f).collect();
+        let mut evals = coeffs.clone();
+        ntt(&mut evals);
+        let shift = Goldilocks::multiplicative_generator();
+        let ext = lde(&evals, 4, shift);
+        let w = root_of_unity::<Goldilocks>(5);
+        assert_eq!(ext, naive_dft(&coeffs, (0..32).map(|i| shift * w.pow(i))));
+        assert_eq!(ext, lde_from_coeffs(&coeffs, 4, shift));
+        // with shift 1 the original evaluations reappear at every 4th point
+        let plain = lde(&evals, 4, f(1));
+        assert_eq!(plain.iter().step_by(4).copied().collect::<Vec<_>>(), evals);
+    }
+}
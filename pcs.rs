@@ -0,0 +1,76 @@
+// pcs.rs — multilinear polynomial commitment schemes for the transparent backends.
+//
+// `MerklePcs` is the simplest binding scheme: commit to the Poseidon Merkle root of the
+// evaluation table and open by revealing the whole table, which the verifier re-hashes and
+// evaluates. It needs no setup and is trivially sound, but openings are as large as the
+// polynomial and reveal it; a succinct scheme can be dropped in behind the same trait.
+
+use crate::field::PrimeField;
+use crate::merkle::MerkleTree;
+use crate::multilinear::MultilinearPolynomial;
+use crate::poseidon::Poseidon;
+use crate::transcript::Transcript;
+use std::fmt::Debug;
+
+pub trait MultilinearPcs<F: PrimeField> {
+    type Commitment: Clone + Debug + PartialEq;
+    type Opening: Clone + Debug;
+
+    fn commit(&self, poly: &MultilinearPolynomial<F>) -> Self::Commitment;
+    fn append_commitment(&self, commitment: &Self::Commitment, transcript: &mut Transcript);
+    // proof that poly(point) = poly.evaluate(point)
+    fn open(&self, poly: &MultilinearPolynomial<F>, point: &[F], transcript: &mut Transcript) -> Self::Opening;
+    fn verify(&self, commitment: &Self::Commitment, point: &[F], value: F, opening: &Self::Opening, transcript: &mut Transcript) -> bool;
+}
+
+#[derive(Clone, Debug)]
+pub struct MerklePcs<F: PrimeField> {
+    pub perm: Poseidon<F>,
+}
+
+impl<F: PrimeField> MerklePcs<F> {
+    pub fn new(perm: Poseidon<F>) -> Self { Self { perm } }
+    fn root(&self, evals: &[F]) -> F { MerkleTree::new(self.perm.clone(), evals.len().trailing_zeros() as usize, evals).root() }
+}
+
+impl<F: PrimeField> MultilinearPcs<F> for MerklePcs<F> {
+    type Commitment = F;
+    // the full evaluation table
+    type Opening = Vec<F>;
+
+    fn commit(&self, poly: &MultilinearPolynomial<F>) -> F { self.root(&poly.evals) }
+    fn append_commitment(&self, commitment: &F, transcript: &mut Transcript) {
+        transcript.append_field(b"merkle pcs root", commitment);
+    }
+    fn open(&self, poly: &MultilinearPolynomial<F>, _point: &[F], _transcript: &mut Transcript) -> Vec<F> {
+        poly.evals.clone()
+    }
+    fn verify(&self, commitment: &F, point: &[F], value: F, opening: &Vec<F>, _transcript: &mut Transcript) -> bool {
+        opening.len() == 1 << point.len()
+            && self.root(opening) == *commitment
+            && MultilinearPolynomial::new(opening.clone()).evaluate(point) == value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Goldilocks;
+
+    #[test]
+    fn merkle_pcs_binds_the_table() {
+        let pcs = MerklePcs::new(Poseidon::goldilocks_t8());
+        let poly = MultilinearPolynomial::new((0..8).map(Goldilocks::from_u64).collect());
+        let com = pcs.commit(&poly);
+        let point = [Goldilocks::from_u64(5), Goldilocks::from_u64(6), Goldilocks::from_u64(7)];
+        let value = poly.evaluate(&point);
+        let mut t = Transcript::new(b"pcs");
+        let opening = pcs.open(&poly, &point, &mut t);
+        assert!(pcs.verify(&com, &point, value, &opening, &mut t));
+        assert!(!pcs.verify(&com, &point, value + Goldilocks::ONE, &opening, &mut t));
+        let mut forged = opening.clone();
+        forged[3] += Goldilocks::ONE;
+        let forged_value = MultilinearPolynomial::new(forged.clone()).evaluate(&point);
+        assert!(!pcs.verify(&com, &point, forged_value, &forged, &mut t));
+    }
+}
@@ -0,0 +1,323 @@
+// poly.rs — dense univariate polynomials, multiplicative evaluation domains and
+// evaluation-form vectors.
+//
+// Coefficients are stored low degree first with trailing zeros trimmed, so the zero
+// polynomial has no coefficients. Products switch from schoolbook to NTT multiplication
+// once both operands are large enough for the transform to pay off.
+
+use crate::field::PrimeField;
+use crate::ntt::{coset_intt, coset_ntt, root_of_unity};
+use std::ops::{Add, Mul, Neg, Sub};
+
+const NTT_MUL_THRESHOLD: usize = 64;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DensePolynomial<F: PrimeField> {
+    pub coeffs: Vec<F>,
+}
+
+impl<F: PrimeField> DensePolynomial<F> {
+    pub fn new(mut coeffs: Vec<F>) -> Self {
+        while coeffs.last().is_some_and(|c| c.is_zero()) { coeffs.pop(); }
+        Self { coeffs }
+    }
+    pub fn zero() -> Self { Self { coeffs: vec![] } }
+    pub fn constant(c: F) -> Self { Self::new(vec![c]) }
+    // X^n - c
+    pub fn x_pow_minus(n: usize, c: F) -> Self {
+        let mut coeffs = vec![F::ZERO; n + 1];
+        coeffs[0] = -c;
+        coeffs[n] = F::ONE;
+        Self::new(coeffs)
+    }
+
+    pub fn is_zero(&self) -> bool { self.coeffs.is_empty() }
+    // degree of the zero polynomial is reported as 0
+    pub fn degree(&self) -> usize { self.coeffs.len().saturating_sub(1) }
+    pub fn leading_coeff(&self) -> F { self.coeffs.last().copied().unwrap_or(F::ZERO) }
+
+    // Horner
+    pub fn evaluate(&self, x: F) -> F {
+        self.coeffs.iter().rev().fold(F::ZERO, |acc, c| acc * x + *c)
+    }
+
+    pub fn scale(&self, k: F) -> Self { Self::new(self.coeffs.iter().map(|c| *c * k).collect()) }
+
+    pub fn mul_naive(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() { return Self::zero(); }
+        let mut out = vec![F::ZERO; self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                out[i + j] += *a * *b;
+            }
+        }
+        Self::new(out)
+    }
+
+    pub fn mul_ntt(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() { return Self::zero(); }
+        let domain = Domain::new(self.coeffs.len() + other.coeffs.len() - 1);
+        let (a, b) = (self.evaluate_over_domain(&domain), other.evaluate_over_domain(&domain));
+        (&a * &b).interpolate()
+    }
+
+    // (q, r) with self = q·divisor + r and deg r < deg divisor
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "division by the zero polynomial");
+        if self.coeffs.len() < divisor.coeffs.len() { return (Self::zero(), self.clone()); }
+        let lead_inv = divisor.leading_coeff().inverse().unwrap();
+        let d = divisor.degree();
+        let mut rem = self.coeffs.clone();
+        let mut q = vec![F::ZERO; rem.len() - d];
+        for i in (0..q.len()).rev() {
+            let k = rem[i + d] * lead_inv;
+            q[i] = k;
+            for (r, c) in rem[i..=i + d].iter_mut().zip(&divisor.coeffs) {
+                *r -= k * *c;
+            }
+        }
+        rem.truncate(d);
+        (Self::new(q), Self::new(rem))
+    }
+
+    // Division by the domain's vanishing polynomial X^n - offset^n in O(deg) time.
+    pub fn divide_by_vanishing(&self, domain: &Domain<F>) -> (Self, Self) {
+        let n = domain.size;
+        if self.coeffs.len() <= n { return (Self::zero(), self.clone()); }
+        let c = domain.offset.pow(n as u64);
+        // X^n ≡ c: fold every coefficient from the top down onto the one n places below
+        let mut rem = self.coeffs.clone();
+        let mut q = vec![F::ZERO; rem.len() - n];
+        for i in (n..rem.len()).rev() {
+            let top = rem[i];
+            q[i - n] = top;
+            rem[i - n] += top * c;
+        }
+        rem.truncate(n);
+        (Self::new(q), Self::new(rem))
+    }
+
+    // Lagrange interpolation through (xs[i], ys[i]), O(n^2); the xs must be distinct.
+    pub fn interpolate(xs: &[F], ys: &[F]) -> Self {
+        assert_eq!(xs.len(), ys.len(), "one value per point");
+        // Z(X) = prod(X - x_j); L_i = Z / (X - x_i) / Z'(x_i)
+        let z = xs.iter().fold(Self::constant(F::ONE), |acc, x| acc.mul_naive(&Self::new(vec![-*x, F::ONE])));
+        let mut out = Self::zero();
+        for (xi, yi) in xs.iter().zip(ys) {
+            let (num, _) = z.div_rem(&Self::new(vec![-*xi, F::ONE]));
+            let denom = num.evaluate(*xi).inverse().expect("interpolation points must be distinct");
+            out = &out + &num.scale(*yi * denom);
+        }
+        out
+    }
+
+    pub fn evaluate_over_domain(&self, domain: &Domain<F>) -> Evaluations<F> {
+        assert!(self.coeffs.len() <= domain.size, "degree {} does not fit domain of size {}", self.degree(), domain.size);
+        let mut evals = self.coeffs.clone();
+        evals.resize(domain.size, F::ZERO);
+        coset_ntt(&mut evals, domain.offset);
+        Evaluations { evals, domain: domain.clone() }
+    }
+}
+
+impl<F: PrimeField> Add for &DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+    fn add(self, rhs: Self) -> DensePolynomial<F> {
+        let n = self.coeffs.len().max(rhs.coeffs.len());
+        let get = |p: &DensePolynomial<F>, i: usize| p.coeffs.get(i).copied().unwrap_or(F::ZERO);
+        DensePolynomial::new((0..n).map(|i| get(self, i) + get(rhs, i)).collect())
+    }
+}
+impl<F: PrimeField> Neg for &DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+    fn neg(self) -> DensePolynomial<F> { DensePolynomial::new(self.coeffs.iter().map(|c| -*c).collect()) }
+}
+impl<F: PrimeField> Sub for &DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+    fn sub(self, rhs: Self) -> DensePolynomial<F> { self + &-rhs }
+}
+impl<F: PrimeField> Mul for &DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+    fn mul(self, rhs: Self) -> DensePolynomial<F> {
+        if self.coeffs.len().min(rhs.coeffs.len()) >= NTT_MUL_THRESHOLD { self.mul_ntt(rhs) } else { self.mul_naive(rhs) }
+    }
+}
+
+// ---- Evaluation domains ----
+// The coset offset·<ω> of size n (a power of two); offset 1 is the subgroup itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Domain<F: PrimeField> {
+    pub size: usize,
+    pub log_size: u32,
+    pub generator: F,
+    pub offset: F,
+}
+
+impl<F: PrimeField> Domain<F> {
+    // smallest subgroup with at least `min_size` elements
+    pub fn new(min_size: usize) -> Self {
+        let size = min_size.max(1).next_power_of_two();
+        let log_size = size.trailing_zeros();
+        Self { size, log_size, generator: root_of_unity(log_size), offset: F::ONE }
+    }
+    pub fn coset(&self, offset: F) -> Self { Self { offset, ..self.clone() } }
+
+    pub fn element(&self, i: usize) -> F { self.offset * self.generator.pow(i as u64) }
+    pub fn elements(&self) -> Vec<F> {
+        std::iter::successors(Some(self.offset), |x| Some(*x * self.generator)).take(self.size).collect()
+    }
+
+    // Z(X) = X^n - offset^n
+    pub fn vanishing_polynomial(&self) -> DensePolynomial<F> {
+        DensePolynomial::x_pow_minus(self.size, self.offset.pow(self.size as u64))
+    }
+    pub fn evaluate_vanishing(&self, x: F) -> F {
+        x.pow(self.size as u64) - self.offset.pow(self.size as u64)
+    }
+
+    // [L_0(τ), .., L_{n-1}(τ)] for the Lagrange basis of the domain. With h = offset:
+    // L_i(τ) = Z(τ)·x_i / (n·h^n·(τ - x_i)), or the indicator vector when τ is in the domain.
+    pub fn lagrange_coeffs_at(&self, tau: F) -> Vec<F> {
+        let xs = self.elements();
+        if let Some(i) = xs.iter().position(|x| *x == tau) {
+            let mut out = vec![F::ZERO; self.size];
+            out[i] = F::ONE;
+            return out;
+        }
+        let z = self.evaluate_vanishing(tau);
+        let denom = F::from_u64(self.size as u64) * self.offset.pow(self.size as u64);
+        let mut inv: Vec<F> = xs.iter().map(|x| denom * (tau - *x)).collect();
+        crate::field::batch_inverse(&mut inv);
+        xs.iter().zip(inv).map(|(x, d)| z * *x * d).collect()
+    }
+}
+
+// ---- Evaluation form ----
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Evaluations<F: PrimeField> {
+    pub evals: Vec<F>,
+    pub domain: Domain<F>,
+}
+
+impl<F: PrimeField> Evaluations<F> {
+    pub fn new(evals: Vec<F>, domain: Domain<F>) -> Self {
+        assert_eq!(evals.len(), domain.size, "one evaluation per domain element");
+        Self { evals, domain }
+    }
+    pub fn interpolate(&self) -> DensePolynomial<F> {
+        let mut coeffs = self.evals.clone();
+        coset_intt(&mut coeffs, self.domain.offset);
+        DensePolynomial::new(coeffs)
+    }
+}
+
+impl<F: PrimeField> Add for &Evaluations<F> {
+    type Output = Evaluations<F>;
+    fn add(self, rhs: Self) -> Evaluations<F> {
+        assert_eq!(self.domain, rhs.domain, "evaluations over different domains");
+        Evaluations::new(self.evals.iter().zip(&rhs.evals).map(|(a, b)| *a + *b).collect(), self.domain.clone())
+    }
+}
+impl<F: PrimeField> Sub for &Evaluations<F> {
+    type Output = Evaluations<F>;
+    fn sub(self, rhs: Self) -> Evaluations<F> {
+        assert_eq!(self.domain, rhs.domain, "evaluations over different domains");
+        Evaluations::new(self.evals.iter().zip(&rhs.evals).map(|(a, b)| *a - *b).collect(), self.domain.clone())
+    }
+}
+impl<F: PrimeField> Mul for &Evaluations<F> {
+    type Output = Evaluations<F>;
+    fn mul(self, rhs: Self) -> Evaluations<F> {
+        assert_eq!(self.domain, rhs.domain, "evaluations over different domains");
+        Evaluations::new(self.evals.iter().zip(&rhs.evals).map(|(a, b)| *a * *b).collect(), self.domain.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Builder, Goldilocks, LinComb};
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+    fn poly(cs: &[u64]) -> DensePolynomial<Goldilocks> { DensePolynomial::new(cs.iter().map(|c| f(*c)).collect()) }
+
+    #[test]
+    fn arithmetic_and_division() {
+        let a = poly(&[1, 2, 3]);
+        let b = poly(&[5, 0, 0, 7, 0]);
+        assert_eq!(b.degree(), 3);
+        assert_eq!(a.evaluate(f(10)), f(321));
+        assert_eq!(&(&a + &b) - &b, a);
+        assert!((&a - &a).is_zero());
+        assert_eq!((&a * &b).evaluate(f(3)), a.evaluate(f(3)) * b.evaluate(f(3)));
+
+        let big: Vec<DensePolynomial<Goldilocks>> = [100u64, 90].iter()
+            .map(|n| DensePolynomial::new((0..*n).map(|i| f(i * 7 + 1) - f(300)).collect()))
+            .collect();
+        assert_eq!(big[0].mul_ntt(&big[1]), big[0].mul_naive(&big[1]));
+
+        let (q, r) = big[0].div_rem(&b);
+        assert!(r.degree() < b.degree());
+        assert_eq!(&(&q * &b) + &r, big[0]);
+        let (q, r) = a.div_rem(&b);
+        assert_eq!((q, r), (DensePolynomial::zero(), a));
+    }
+
+    #[test]
+    fn vanishing_division_and_domains() {
+        for offset in [f(1), Goldilocks::multiplicative_generator()] {
+            let domain = Domain::<Goldilocks>::new(8).coset(offset);
+            let z = domain.vanishing_polynomial();
+            assert!(domain.elements().iter().all(|x| z.evaluate(*x) == f(0)));
+            let p = DensePolynomial::new((0..21).map(|i| f(i * i + 3)).collect());
+            let (q, r) = p.divide_by_vanishing(&domain);
+            assert_eq!((q.clone(), r.clone()), p.div_rem(&z));
+            assert_eq!(&(&q * &z) + &r, p);
+
+            let small = DensePolynomial::new((0..8).map(|i| f(i + 1)).collect());
+            let evals = small.evaluate_over_domain(&domain);
+            assert_eq!(evals.evals, domain.elements().iter().map(|x| small.evaluate(*x)).collect::<Vec<_>>());
+            assert_eq!(evals.interpolate(), small);
+            let tau = f(123456789);
+            let l = domain.lagrange_coeffs_at(tau);
+            assert_eq!(l.iter().zip(&evals.evals).fold(f(0), |acc, (li, e)| acc + *li * *e), small.evaluate(tau));
+            assert_eq!(domain.lagrange_coeffs_at(domain.element(3))[3], f(1));
+        }
+    }
+
+    #[test]
+    fn lagrange_interpolation() {
+        let xs = [f(2), f(5), -f(1), f(100)];
+        let ys = [f(7), f(0), f(3), f(11)];
+        let p = DensePolynomial::interpolate(&xs, &ys);
+        assert!(p.degree() <= 3);
+        for (x, y) in xs.iter().zip(ys) {
+            assert_eq!(p.evaluate(*x), y);
+        }
+    }
+
+    // Builder constraints as a QAP: interpolate each constraint column over a domain and
+    // check that A(X)·B(X) - C(X) vanishes on it exactly when the witness satisfies R1CS.
+    #[test]
+    fn r1cs_to_qap() {
+        let mut b = Builder::new();
+        let x = b.alloc(f(3));
+        let x3 = b.pow_lc(&LinComb::var(x), 3);
+        let out = b.alloc_public(f(35));
+        b.constrain(x3.add_scaled(&LinComb::var(x), f(1)).c(f(5)), LinComb::constant(f(1)), LinComb::var(out));
+        let domain = Domain::new(b.constraints.len());
+        let remainder = |w: &crate::Witness<Goldilocks>| {
+            let column = |sel: fn(&crate::Constraint<Goldilocks>) -> &LinComb<Goldilocks>| {
+                let mut evals: Vec<Goldilocks> = b.constraints.iter().map(|c| sel(c).eval(w)).collect();
+                evals.resize(domain.size, f(0));
+                Evaluations::new(evals, domain.clone()).interpolate()
+            };
+            let (pa, pb, pc) = (column(|c| &c.a), column(|c| &c.b), column(|c| &c.c));
+            (&(&pa * &pb) - &pc).divide_by_vanishing(&domain).1
+        };
+        let mut w = b.witness();
+        assert!(remainder(&w).is_zero());
+        w.values.insert(out, f(36));
+        assert!(!remainder(&w).is_zero());
+    }
+}
@@ -0,0 +1,521 @@
+// poseidon.rs — the Poseidon permutation (Grassi et al., "Poseidon: A New Hash Function for
+// Zero-Knowledge Proof Systems"), configurable over any `PrimeField`.
+//
+// A permutation of width t runs R_F/2 full rounds, R_P partial rounds, then R_F/2 full rounds.
+// Each round: add round constants (ARK), S-box x^alpha (all cells in full rounds, cell 0 in
+// partial rounds), multiply by the MDS matrix.
+//
+// Round constants and the Cauchy MDS matrix come from the Grain LFSR exactly as in the
+// reference generate_parameters_grain.sage, so `Poseidon::new` reproduces reference
+// parameters. The shipped instances (BN254 t = 3 / 5, Goldilocks t = 8 / 12) are checked
+// against published test vectors. The reference additionally re-samples MDS matrices that
+// fail its subspace-trail checks; that step is not implemented, so audit the matrix of any
+// new parameter set.
+//
+// The sponge keeps its capacity at the front of the state (cell 0 carries the domain tag)
+// and absorbs into / squeezes from the rate cells after it. `two_to_one` is the
+// circomlib-style compression perm([0, l, r, ..])[0].
+//
+// The Builder gadgets mirror the native code exactly. State cells are `LinComb`s, so ARK and
+// MDS layers are free; each S-box costs one constraint per square-and-multiply step
+// (x^2, x^4, x^5 for alpha = 5).
+
+use crate::field::{limbs_ge, limbs_to_field, PrimeField};
+use crate::{Builder, Goldilocks, LinComb};
+
+#[derive(Clone, Debug)]
+pub struct Poseidon<F: PrimeField> {
+    pub width: usize,
+    pub alpha: u64,
+    pub rounds_f: usize,
+    pub rounds_p: usize,
+    // one row of `width` constants per round
+    pub round_constants: Vec<Vec<F>>,
+    pub mds: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> Poseidon<F> {
+    // Grain-derived round constants and MDS for (t, alpha, R_F, R_P).
+    pub fn new(width: usize, alpha: u64, rounds_f: usize, rounds_p: usize) -> Self {
+        Self::from_grain(width, alpha, rounds_f, rounds_p, GRAIN_SBOX_POW)
+    }
+
+    // As `new`, with an explicit S-box field in the Grain header.
+    fn from_grain(width: usize, alpha: u64, rounds_f: usize, rounds_p: usize, sbox: u64) -> Self {
+        assert!(rounds_f.is_multiple_of(2), "R_F must be even");
+        let mut grain = GrainLfsr::new(F::MODULUS_BITS, sbox, width, rounds_f, rounds_p);
+        let round_constants = (0..rounds_f + rounds_p)
+            .map(|_| (0..width).map(|_| grain.next_field_element::<F>()).collect())
+            .collect();
+        let mds = grain.cauchy_mds::<F>(width);
+        Self { width, alpha, rounds_f, rounds_p, round_constants, mds }
+    }
+
+    pub fn with_mds(mut self, mds: Vec<Vec<F>>) -> Self {
+        assert!(mds.len() == self.width && mds.iter().all(|r| r.len() == self.width));
+        self.mds = mds;
+        self
+    }
+
+    pub fn num_rounds(&self) -> usize { self.rounds_f + self.rounds_p }
+    pub fn is_full_round(&self, r: usize) -> bool {
+        r < self.rounds_f / 2 || r >= self.rounds_f / 2 + self.rounds_p
+    }
+
+    pub fn mds_mul(&self, state: &[F]) -> Vec<F> {
+        self.mds.iter()
+            .map(|row| row.iter().zip(state).fold(F::ZERO, |acc, (m, x)| acc + *m * *x))
+            .collect()
+    }
+
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width, "state width mismatch");
+        for r in 0..self.num_rounds() {
+            for (x, c) in state.iter_mut().zip(&self.round_constants[r]) {
+                *x += *c;
+            }
+            if self.is_full_round(r) {
+                for x in state.iter_mut() { *x = x.pow(self.alpha); }
+            } else {
+                state[0] = state[0].pow(self.alpha);
+            }
+            let next = self.mds_mul(state);
+            state.copy_from_slice(&next);
+        }
+    }
+}
+
+impl Poseidon<Goldilocks> {
+    // The HorizenLabs reference instances: x^7 (the smallest alpha coprime to p - 1),
+    // R_F = 8, R_P = 22. Their constants were generated with the Grain S-box field set to
+    // x^-1, which is reproduced here to match the published vectors. (Plonky2 uses its own
+    // constants and a circulant MDS, so its outputs differ.)
+    pub fn goldilocks_t8() -> Self { Self::from_grain(8, 7, 8, 22, GRAIN_SBOX_INV) }
+    pub fn goldilocks_t12() -> Self { Self::from_grain(12, 7, 8, 22, GRAIN_SBOX_INV) }
+}
+
+#[cfg(feature = "bn254")]
+impl Poseidon<crate::bn254::Fr> {
+    // the reference / circomlib instances: x^5, R_F = 8, R_P = 57 (t = 3) or 60 (t = 5)
+    pub fn bn254_t3() -> Self { Self::new(3, 5, 8, 57) }
+    pub fn bn254_t5() -> Self { Self::new(5, 5, 8, 60) }
+}
+
+// ---- Sponge ----
+
+// Capacity tag per hashing mode, so different modes never collide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Domain {
+    // two_to_one compression (tag 0, circomlib-compatible)
+    Merkle,
+    // fixed number of inputs, known to both sides
+    ConstantLength(usize),
+    // arbitrary-length input with 10* padding
+    VariableLength,
+    // application-defined separation
+    Custom(u64),
+}
+
+impl Domain {
+    // 4·x + mode, computed without truncation; panics if it does not fit below the modulus
+    // (where distinct tags could wrap onto each other)
+    pub fn tag<F: PrimeField>(&self) -> F {
+        let (x, mode) = match *self {
+            Domain::Merkle => (0, 0),
+            Domain::ConstantLength(n) => (n as u64, 1),
+            Domain::VariableLength => (0, 2),
+            Domain::Custom(x) => (x, 3),
+        };
+        let v = ((x as u128) << 2) | mode;
+        let limbs = [v as u64, (v >> 64) as u64];
+        assert!(!limbs_ge(&limbs, F::MODULUS), "domain tag {self:?} does not fit in the field");
+        limbs_to_field(&limbs)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PoseidonSponge<'a, F: PrimeField> {
+    perm: &'a Poseidon<F>,
+    state: Vec<F>,
+    rate: usize,
+    // next rate cell to absorb into / squeeze from
+    pos: usize,
+    squeezing: bool,
+}
+
+impl<'a, F: PrimeField> PoseidonSponge<'a, F> {
+    // capacity 1, rate t - 1
+    pub fn new(perm: &'a Poseidon<F>, domain: Domain) -> Self { Self::with_rate(perm, perm.width - 1, domain) }
+
+    pub fn with_rate(perm: &'a Poseidon<F>, rate: usize, domain: Domain) -> Self {
+        assert!(rate >= 1 && rate < perm.width, "need 1 <= rate < width");
+        let mut state = vec![F::ZERO; perm.width];
+        state[0] = domain.tag();
+        Self { perm, state, rate, pos: 0, squeezing: false }
+    }
+
+    fn capacity(&self) -> usize { self.perm.width - self.rate }
+
+    pub fn absorb(&mut self, inputs: &[F]) {
+        if self.squeezing {
+            // duplex: squeezed cells must be permuted before new input lands in them
+            self.squeezing = false;
+            self.pos = self.rate;
+        }
+        for x in inputs {
+            if self.pos == self.rate {
+                self.perm.permute(&mut self.state);
+                self.pos = 0;
+            }
+            let c = self.capacity();
+            self.state[c + self.pos] += *x;
+            self.pos += 1;
+        }
+    }
+
+    pub fn squeeze(&mut self, n: usize) -> Vec<F> {
+        (0..n).map(|_| self.squeeze_one()).collect()
+    }
+
+    pub fn squeeze_one(&mut self) -> F {
+        if !self.squeezing || self.pos == self.rate {
+            self.perm.permute(&mut self.state);
+            self.squeezing = true;
+            self.pos = 0;
+        }
+        let out = self.state[self.capacity() + self.pos];
+        self.pos += 1;
+        out
+    }
+}
+
+// Fixed-length hash; the input length is bound through the domain tag.
+pub fn hash_n_to_one<F: PrimeField>(perm: &Poseidon<F>, inputs: &[F]) -> F {
+    let mut sponge = PoseidonSponge::new(perm, Domain::ConstantLength(inputs.len()));
+    sponge.absorb(inputs);
+    sponge.squeeze_one()
+}
+
+// Variable-length hash: inputs are padded with 1 then zeros up to a multiple of the rate.
+pub fn hash_padded<F: PrimeField>(perm: &Poseidon<F>, inputs: &[F], n_out: usize) -> Vec<F> {
+    let mut sponge = PoseidonSponge::new(perm, Domain::VariableLength);
+    let mut padded = inputs.to_vec();
+    padded.push(F::ONE);
+    while !padded.len().is_multiple_of(sponge.rate) { padded.push(F::ZERO); }
+    sponge.absorb(&padded);
+    sponge.squeeze(n_out)
+}
+
+// Merkle compression: perm([0, left, right, 0, ..])[0] (circomlib's Poseidon(2) for BN254 t = 3).
+pub fn two_to_one<F: PrimeField>(perm: &Poseidon<F>, left: F, right: F) -> F {
+    assert!(perm.width >= 3, "two_to_one needs width >= 3");
+    let mut state = vec![F::ZERO; perm.width];
+    state[0] = Domain::Merkle.tag();
+    state[1] = left;
+    state[2] = right;
+    perm.permute(&mut state);
+    state[0]
+}
+
+// ---- Wide digests ----
+// One element of a 64-bit field is a ~32-bit collision-resistant digest. These hashes keep
+// DIGEST_LEN elements of capacity and squeeze DIGEST_LEN elements (~128 bits on Goldilocks).
+pub const DIGEST_LEN: usize = 4;
+pub type Digest<F> = [F; DIGEST_LEN];
+
+fn wide_sponge<F: PrimeField>(perm: &Poseidon<F>, domain: Domain) -> PoseidonSponge<'_, F> {
+    assert!(perm.width > DIGEST_LEN, "wide digests need width > {DIGEST_LEN}");
+    PoseidonSponge::with_rate(perm, perm.width - DIGEST_LEN, domain)
+}
+
+pub fn hash_n_to_digest<F: PrimeField>(perm: &Poseidon<F>, inputs: &[F]) -> Digest<F> {
+    let mut sponge = wide_sponge(perm, Domain::ConstantLength(inputs.len()));
+    sponge.absorb(inputs);
+    std::array::from_fn(|_| sponge.squeeze_one())
+}
+
+// Merkle compression of two digests (domain-separated from leaf hashing)
+pub fn compress_digests<F: PrimeField>(perm: &Poseidon<F>, left: &Digest<F>, right: &Digest<F>) -> Digest<F> {
+    let mut sponge = wide_sponge(perm, Domain::Merkle);
+    sponge.absorb(left);
+    sponge.absorb(right);
+    std::array::from_fn(|_| sponge.squeeze_one())
+}
+
+// ---- In-circuit gadgets ----
+impl<F: PrimeField> Builder<F> {
+    pub fn poseidon_permutation(&mut self, perm: &Poseidon<F>, state: &[LinComb<F>]) -> Vec<LinComb<F>> {
+        assert_eq!(state.len(), perm.width, "state width mismatch");
+        let mut state = state.to_vec();
+        for r in 0..perm.num_rounds() {
+            for (x, c) in state.iter_mut().zip(&perm.round_constants[r]) {
+                *x = std::mem::take(x).c(*c);
+            }
+            let sboxed = if perm.is_full_round(r) { perm.width } else { 1 };
+            for x in state.iter_mut().take(sboxed) {
+                *x = self.pow_lc(x, perm.alpha);
+            }
+            state = perm.mds.iter()
+                .map(|row| row.iter().zip(&state).fold(LinComb::new(), |acc, (m, x)| acc.add_scaled(x, *m)))
+                .collect();
+        }
+        state
+    }
+
+    // in-circuit `two_to_one`
+    pub fn poseidon_two_to_one(&mut self, perm: &Poseidon<F>, left: usize, right: usize) -> usize {
+        let out = self.poseidon_compress(perm, &LinComb::var(left), &LinComb::var(right));
+        self.to_var(&out)
+    }
+
+    // `two_to_one` on linear combinations, output left unallocated
+    pub fn poseidon_compress(&mut self, perm: &Poseidon<F>, left: &LinComb<F>, right: &LinComb<F>) -> LinComb<F> {
+        let mut state = vec![LinComb::new(); perm.width];
+        state[0] = LinComb::constant(Domain::Merkle.tag());
+        state[1] = left.clone();
+        state[2] = right.clone();
+        self.poseidon_permutation(perm, &state).swap_remove(0)
+    }
+
+    // in-circuit `hash_n_to_one` (capacity 1, rate t - 1)
+    pub fn poseidon_hash_n_to_one(&mut self, perm: &Poseidon<F>, inputs: &[usize]) -> usize {
+        let rate = perm.width - 1;
+        let mut state = vec![LinComb::new(); perm.width];
+        state[0] = LinComb::constant(Domain::ConstantLength(inputs.len()).tag());
+        for (i, chunk) in inputs.chunks(rate).enumerate() {
+            if i > 0 { state = self.poseidon_permutation(perm, &state); }
+            for (cell, x) in state[1..].iter_mut().zip(chunk) {
+                *cell = std::mem::take(cell).add_scaled(&LinComb::var(*x), F::ONE);
+            }
+        }
+        let out = self.poseidon_permutation(perm, &state);
+        self.to_var(&out[1])
+    }
+}
+
+// ---- Grain LFSR (80-bit, self-shrinking) ----
+pub struct GrainLfsr {
+    // bit i of the reference sequence is bit i here
+    state: u128,
+}
+
+// S-box field of the Grain header
+pub const GRAIN_SBOX_POW: u64 = 0; // x^alpha
+pub const GRAIN_SBOX_INV: u64 = 1; // x^-1
+
+impl GrainLfsr {
+    // field = 1 (prime field)
+    pub fn new(field_bits: u32, sbox: u64, width: usize, rounds_f: usize, rounds_p: usize) -> Self {
+        let fields: [(u64, u32); 7] = [
+            (1, 2), (sbox, 4), (field_bits as u64, 12), (width as u64, 12),
+            (rounds_f as u64, 10), (rounds_p as u64, 10), ((1 << 30) - 1, 30),
+        ];
+        let mut state = 0u128;
+        let mut pos = 0;
+        for (value, len) in fields {
+            for i in (0..len).rev() {
+                state |= (((value >> i) & 1) as u128) << pos;
+                pos += 1;
+            }
+        }
+        let mut g = Self { state };
+        for _ in 0..160 { g.clock(); }
+        g
+    }
+
+    fn clock(&mut self) -> bool {
+        let s = self.state;
+        let bit = ((s >> 62) ^ (s >> 51) ^ (s >> 38) ^ (s >> 23) ^ (s >> 13) ^ s) & 1;
+        self.state = (s >> 1) | (bit << 79);
+        bit == 1
+    }
+
+    // bits come in pairs; the second is kept only if the first is 1
+    pub fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.clock();
+            let bit = self.clock();
+            if keep { return bit; }
+        }
+    }
+
+    // `n` bits read as a big-endian integer, as little-endian u64 limbs
+    fn next_limbs(&mut self, n: u32) -> Vec<u64> {
+        let mut limbs = vec![0u64; n.div_ceil(64) as usize];
+        for i in (0..n).rev() {
+            if self.next_bit() { limbs[(i / 64) as usize] |= 1 << (i % 64); }
+        }
+        limbs
+    }
+
+    // uniform element by rejection sampling of MODULUS_BITS-bit integers
+    pub fn next_field_element<F: PrimeField>(&mut self) -> F {
+        loop {
+            let limbs = self.next_limbs(F::MODULUS_BITS);
+            if !limbs_ge(&limbs, F::MODULUS) { return limbs_to_field(&limbs); }
+        }
+    }
+
+    // MODULUS_BITS-bit integer reduced mod p (no rejection)
+    pub fn next_field_element_reduced<F: PrimeField>(&mut self) -> F {
+        limbs_to_field(&self.next_limbs(F::MODULUS_BITS))
+    }
+
+    // M[i][j] = 1 / (x_i + y_j) with 2t distinct Grain samples x, y
+    pub fn cauchy_mds<F: PrimeField>(&mut self, t: usize) -> Vec<Vec<F>> {
+        loop {
+            let mut samples: Vec<F> = (0..2 * t).map(|_| self.next_field_element_reduced()).collect();
+            while (0..samples.len()).any(|i| samples[..i].contains(&samples[i])) {
+                samples = (0..2 * t).map(|_| self.next_field_element_reduced()).collect();
+            }
+            let (xs, ys) = samples.split_at(t);
+            let m: Option<Vec<Vec<F>>> = xs.iter()
+                .map(|x| ys.iter().map(|y| (*x + *y).inverse()).collect())
+                .collect();
+            if let Some(m) = m { return m; }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goldilocks_params_are_well_formed() {
+        for p in [Poseidon::goldilocks_t8(), Poseidon::goldilocks_t12()] {
+            assert_eq!(p.round_constants.len(), 30);
+            assert!(p.round_constants.iter().all(|rc| rc.len() == p.width));
+            // Cauchy matrices have no zero entries
+            assert!(p.mds.iter().flatten().all(|m| !m.is_zero()));
+            let mut a: Vec<Goldilocks> = (0..p.width as u64).map(Goldilocks::from_u64).collect();
+            let mut b = a.clone();
+            b[0] += Goldilocks::ONE;
+            p.permute(&mut a);
+            p.permute(&mut b);
+            assert!(a.iter().zip(&b).all(|(x, y)| x != y));
+        }
+    }
+
+    fn permute_iota(p: &Poseidon<Goldilocks>) -> Vec<u64> {
+        let mut state: Vec<Goldilocks> = (0..p.width as u64).map(Goldilocks::from_u64).collect();
+        p.permute(&mut state);
+        state.iter().map(|x| x.to_canonical_u64()).collect()
+    }
+
+    #[test]
+    fn goldilocks_matches_reference() {
+        // perm([0, 1, .., 11]): the published HorizenLabs test vector
+        assert_eq!(permute_iota(&Poseidon::goldilocks_t12()), [
+            0xe9ad770762f48ef5, 0xc12796961ddc7859, 0xa61b71de9595e016, 0xead9e6aa583aafa3,
+            0x93e297beff76e95b, 0x53abd3c5c2a0e924, 0xf3bc50e655c74f51, 0x246cac41b9a45d84,
+            0xcc7f9314b2341f4f, 0xf5f071587c83415c, 0x09486cf35116fba3, 0x9d82aaf136b5c38a,
+        ]);
+        // perm([0, 1, .., 7]) as computed by the same reference implementation (zkhash 0.2.0)
+        assert_eq!(permute_iota(&Poseidon::goldilocks_t8()), [
+            0x5b5078c19e437e2b, 0x6db9adafec0a4c48, 0xaed43d408bfbdf63, 0xcc182cf3ddeb9085,
+            0xae8530859e1cc76d, 0xe1344863fa800441, 0x8f2c53caa00a7c88, 0x508f9688589d9bdf,
+        ]);
+    }
+
+    #[test]
+    fn sponge_modes() {
+        let p = Poseidon::goldilocks_t8();
+        let xs: Vec<Goldilocks> = (1..=20).map(Goldilocks::from_u64).collect();
+
+        // incremental absorption equals one-shot
+        let mut a = PoseidonSponge::new(&p, Domain::Custom(7));
+        a.absorb(&xs[..3]);
+        a.absorb(&xs[3..]);
+        let mut b = PoseidonSponge::new(&p, Domain::Custom(7));
+        b.absorb(&xs);
+        assert_eq!(a.squeeze(10), b.squeeze(10));
+
+        // modes and lengths are separated
+        let h = hash_n_to_one(&p, &xs[..2]);
+        assert_ne!(h, hash_padded(&p, &xs[..2], 1)[0]);
+        assert_ne!(h, two_to_one(&p, xs[0], xs[1]));
+        assert_ne!(hash_padded(&p, &xs[..1], 1), hash_padded(&p, &[xs[0], Goldilocks::ZERO], 1));
+        assert_ne!(hash_n_to_one(&p, &xs[..1]), hash_n_to_one(&p, &[xs[0], Goldilocks::ZERO]));
+    }
+
+    #[test]
+    fn custom_tags_are_not_truncated() {
+        assert_eq!(Domain::Custom(5).tag::<Goldilocks>(), Goldilocks::from_u64(23));
+        // largest x with 4·x + 3 < p
+        let top = (Goldilocks::MODULUS[0] - 1) / 4 - 1;
+        assert_eq!(Domain::Custom(top).tag::<Goldilocks>(), Goldilocks::ZERO - Goldilocks::from_u64(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn oversized_custom_tag_is_rejected() {
+        // 4·x + 3 >= p would alias a smaller tag
+        Domain::Custom((Goldilocks::MODULUS[0] - 1) / 4).tag::<Goldilocks>();
+    }
+
+    #[test]
+    fn gadget_matches_native() {
+        let p = Poseidon::goldilocks_t8();
+        let xs: Vec<Goldilocks> = (0..8).map(|i| Goldilocks::from_u64(i * 1000 + 7)).collect();
+        let mut b = Builder::new();
+        let vars: Vec<usize> = xs.iter().map(|x| b.alloc(*x)).collect();
+        let state: Vec<LinComb<Goldilocks>> = vars.iter().map(|v| LinComb::var(*v)).collect();
+        let out = b.poseidon_permutation(&p, &state);
+        // x^7 = x^2, x^3, x^6, x^7: four constraints per S-box, nothing for ARK / MDS
+        assert_eq!(b.constraints.len(), 4 * (8 * 8 + 22));
+        let mut native = xs.clone();
+        p.permute(&mut native);
+        assert_eq!(out.iter().map(|lc| b.eval_lc(lc).unwrap()).collect::<Vec<_>>(), native);
+
+        let h = b.poseidon_hash_n_to_one(&p, &vars);
+        let m = b.poseidon_two_to_one(&p, vars[1], vars[2]);
+        assert_eq!(b.value(h), Some(hash_n_to_one(&p, &xs)));
+        assert_eq!(b.value(m), Some(two_to_one(&p, xs[1], xs[2])));
+        assert!(crate::verify(&b, &b.witness()));
+
+        // a wrong output value is caught
+        let mut w = b.witness();
+        w.values.insert(h, Goldilocks::ZERO);
+        assert!(!crate::verify(&b, &w));
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn two_to_one_matches_circomlib() {
+        use crate::bn254::Fr;
+        // circomlibjs poseidon([1, 2])
+        let h = two_to_one(&Poseidon::<Fr>::bn254_t3(), Fr::from_u64(1), Fr::from_u64(2));
+        assert_eq!(h.to_string(), "0x115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a");
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn bn254_t3_matches_reference() {
+        use crate::bn254::Fr;
+        let p = Poseidon::<Fr>::bn254_t3();
+        let mut state = [Fr::from_u64(0), Fr::from_u64(1), Fr::from_u64(2)];
+        p.permute(&mut state);
+        assert_eq!(state.map(|x| x.to_string()), [
+            "0x115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+            "0x0fca49b798923ab0239de1c9e7a4a9a2210312b6a2f616d18b5a87f9b628ae29",
+            "0x0e7ae82e40091e63cbd4f16a6d16310b3729d4b6e138fcf54110e2867045a30c",
+        ]);
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn bn254_t5_matches_reference() {
+        use crate::bn254::Fr;
+        let p = Poseidon::<Fr>::bn254_t5();
+        let mut state = [0, 1, 2, 3, 4].map(Fr::from_u64);
+        p.permute(&mut state);
+        assert_eq!(state.map(|x| x.to_string()), [
+            "0x299c867db6c1fdd79dcefa40e4510b9837e60ebb1ce0663dbaa525df65250465",
+            "0x1148aaef609aa338b27dafd89bb98862d8bb2b429aceac47d86206154ffe053d",
+            "0x24febb87fed7462e23f6665ff9a0111f4044c38ee1672c1ac6b0637d34f24907",
+            "0x0eb08f6d809668a981c186beaf6110060707059576406b248e5d9cf6e78b3d3e",
+            "0x07748bc6877c9b82c8b98666ee9d0626ec7f5be4205f79ee8528ef1c4a376fc7",
+        ]);
+    }
+}
@@ -0,0 +1,248 @@
+// poseidon2.rs — the Poseidon2 permutation (Grassi, Khovratovich, Schofnegger 2023) over
+// Goldilocks, widths 8 / 12 / 16, x^7, R_F = 8, R_P = 22.
+//
+//   state = M_E · state
+//   R_F/2 external rounds: add t constants, S-box every cell, M_E
+//   R_P   internal rounds: add 1 constant to cell 0, S-box cell 0, M_I
+//   R_F/2 external rounds
+//
+// M_E = circ(2·M4, M4, .., M4) built from the 4x4 matrix M4 of the paper, and
+// M_I = J + diag(d) (J all-ones), i.e. (M_I·x)_i = sum(x) + d_i·x_i — both cost O(t) adds.
+//
+// Round constants are drawn from the Poseidon Grain LFSR in round order (t per external
+// round, one per internal round), which reproduces the HorizenLabs reference constants.
+// `new` draws the diagonal d from the same stream afterwards, re-sampled until M_I is
+// invertible; the reference diagonals were chosen separately, so the Goldilocks instances
+// pin them. Those instances match the reference test vectors.
+
+use crate::field::PrimeField;
+use crate::poseidon::{GrainLfsr, GRAIN_SBOX_POW};
+use crate::{Builder, Goldilocks, LinComb};
+
+const M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+#[derive(Clone, Debug)]
+pub struct Poseidon2<F: PrimeField> {
+    pub width: usize,
+    pub alpha: u64,
+    pub rounds_f: usize,
+    pub rounds_p: usize,
+    // R_F rows of `width` constants
+    pub external_constants: Vec<Vec<F>>,
+    // R_P constants, added to cell 0
+    pub internal_constants: Vec<F>,
+    // M_I = J + diag(internal_diag)
+    pub internal_diag: Vec<F>,
+}
+
+impl<F: PrimeField> Poseidon2<F> {
+    pub fn new(width: usize, alpha: u64, rounds_f: usize, rounds_p: usize) -> Self {
+        assert!(width >= 4 && width.is_multiple_of(4), "Poseidon2 external layer needs 4 | t");
+        assert!(rounds_f.is_multiple_of(2), "R_F must be even");
+        let mut grain = GrainLfsr::new(F::MODULUS_BITS, GRAIN_SBOX_POW, width, rounds_f, rounds_p);
+        // in round order: first external half, internal rounds, second external half
+        let row = |g: &mut GrainLfsr| -> Vec<F> { (0..width).map(|_| g.next_field_element()).collect() };
+        let mut external_constants: Vec<Vec<F>> = (0..rounds_f / 2).map(|_| row(&mut grain)).collect();
+        let internal_constants = (0..rounds_p).map(|_| grain.next_field_element()).collect();
+        external_constants.extend((0..rounds_f / 2).map(|_| row(&mut grain)));
+        let internal_diag = loop {
+            let d: Vec<F> = (0..width).map(|_| grain.next_field_element()).collect();
+            if internal_matrix_invertible(&d) { break d; }
+        };
+        Self { width, alpha, rounds_f, rounds_p, external_constants, internal_constants, internal_diag }
+    }
+
+    pub fn with_internal_diag(mut self, diag: Vec<F>) -> Self {
+        assert_eq!(diag.len(), self.width, "diagonal width mismatch");
+        assert!(internal_matrix_invertible(&diag), "M_I must be invertible");
+        self.internal_diag = diag;
+        self
+    }
+
+    pub fn external_linear_layer(&self, state: &mut [F]) {
+        let m4 = M4.map(|row| row.map(F::from_u64));
+        let blocks: Vec<[F; 4]> = state.chunks(4)
+            .map(|x| m4.map(|row| row.iter().zip(x).fold(F::ZERO, |acc, (m, v)| acc + *m * *v)))
+            .collect();
+        let mut sums = [F::ZERO; 4];
+        for blk in &blocks {
+            for (s, y) in sums.iter_mut().zip(blk) { *s += *y; }
+        }
+        for (i, x) in state.iter_mut().enumerate() {
+            *x = blocks[i / 4][i % 4] + sums[i % 4];
+        }
+    }
+
+    pub fn internal_linear_layer(&self, state: &mut [F]) {
+        let sum = state.iter().fold(F::ZERO, |acc, x| acc + *x);
+        for (x, d) in state.iter_mut().zip(&self.internal_diag) {
+            *x = sum + *d * *x;
+        }
+    }
+
+    fn external_round(&self, state: &mut [F], rc: &[F]) {
+        for (x, c) in state.iter_mut().zip(rc) {
+            *x = (*x + *c).pow(self.alpha);
+        }
+        self.external_linear_layer(state);
+    }
+
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width, "state width mismatch");
+        let half = self.rounds_f / 2;
+        self.external_linear_layer(state);
+        for rc in &self.external_constants[..half] {
+            self.external_round(state, rc);
+        }
+        for c in &self.internal_constants {
+            state[0] = (state[0] + *c).pow(self.alpha);
+            self.internal_linear_layer(state);
+        }
+        for rc in &self.external_constants[half..] {
+            self.external_round(state, rc);
+        }
+    }
+}
+
+// det(J + diag(d)) = prod(d_i) · (1 + sum(1/d_i)) for non-zero d_i
+fn internal_matrix_invertible<F: PrimeField>(d: &[F]) -> bool {
+    let inv: Option<Vec<F>> = d.iter().map(|x| x.inverse()).collect();
+    match inv {
+        Some(inv) => !inv.iter().fold(F::ONE, |acc, x| acc + *x).is_zero(),
+        None => false,
+    }
+}
+
+// M_I - J diagonals of the HorizenLabs Goldilocks instances (MAT_DIAG{8,12,16}_M_1)
+const GOLDILOCKS_DIAG8: [u64; 8] = [
+    0xa98811a1fed4e3a5, 0x1cc48b54f377e2a0, 0xe40cd4f6c5609a26, 0x11de79ebca97a4a3,
+    0x9177c73d8b7e929c, 0x2a6fe8085797e791, 0x3de6e93329f8d5ad, 0x3f7af9125da962fe,
+];
+const GOLDILOCKS_DIAG12: [u64; 12] = [
+    0xc3b6c08e23ba9300, 0xd84b5de94a324fb6, 0x0d0c371c5b35b84f, 0x7964f570e7188037,
+    0x5daf18bbd996604b, 0x6743bc47b9595257, 0x5528b9362c59bb70, 0xac45e25b7127b68b,
+    0xa2077d7dfbb606b5, 0xf3faac6faee378ae, 0x0c6388b51545e883, 0xd27dbb6944917b60,
+];
+const GOLDILOCKS_DIAG16: [u64; 16] = [
+    0xde9b91a467d6afc0, 0xc5f16b9c76a9be17, 0x0ab0fef2d540ac55, 0x3001d27009d05773,
+    0xed23b1f906d3d9eb, 0x5ce73743cba97054, 0x1c3bab944af4ba24, 0x2faa105854dbafae,
+    0x53ffb3ae6d421a10, 0xbcda9df8884ba396, 0xfc1273e4a31807bb, 0xc77952573d5142c0,
+    0x56683339a819b85e, 0x328fcbd8f0ddc8eb, 0xb5101e303fce9cb7, 0x774487b8c40089bb,
+];
+
+impl Poseidon2<Goldilocks> {
+    // the HorizenLabs reference instances: x^7, R_F = 8, R_P = 22
+    pub fn goldilocks_t8() -> Self { Self::goldilocks(8, &GOLDILOCKS_DIAG8) }
+    pub fn goldilocks_t12() -> Self { Self::goldilocks(12, &GOLDILOCKS_DIAG12) }
+    pub fn goldilocks_t16() -> Self { Self::goldilocks(16, &GOLDILOCKS_DIAG16) }
+
+    fn goldilocks(width: usize, diag: &[u64]) -> Self {
+        Self::new(width, 7, 8, 22).with_internal_diag(diag.iter().map(|d| Goldilocks::from_u64(*d)).collect())
+    }
+}
+
+// ---- In-circuit gadget ----
+impl<F: PrimeField> Builder<F> {
+    pub fn poseidon2_permutation(&mut self, perm: &Poseidon2<F>, state: &[LinComb<F>]) -> Vec<LinComb<F>> {
+        assert_eq!(state.len(), perm.width, "state width mismatch");
+        let t = perm.width;
+        // linear layers act on LinCombs with the same formulas as the native code
+        let external = |s: &[LinComb<F>]| -> Vec<LinComb<F>> {
+            let blocks: Vec<Vec<LinComb<F>>> = s.chunks(4)
+                .map(|x| M4.iter().map(|row| {
+                    row.iter().zip(x).fold(LinComb::new(), |acc, (m, v)| acc.add_scaled(v, F::from_u64(*m)))
+                }).collect())
+                .collect();
+            let sums: Vec<LinComb<F>> = (0..4)
+                .map(|j| blocks.iter().fold(LinComb::new(), |acc, b| acc.add_scaled(&b[j], F::ONE)))
+                .collect();
+            (0..t).map(|i| blocks[i / 4][i % 4].clone().add_scaled(&sums[i % 4], F::ONE)).collect()
+        };
+        let internal = |s: &[LinComb<F>]| -> Vec<LinComb<F>> {
+            let sum = s.iter().fold(LinComb::new(), |acc, x| acc.add_scaled(x, F::ONE));
+            s.iter().zip(&perm.internal_diag).map(|(x, d)| sum.clone().add_scaled(x, *d)).collect()
+        };
+
+        let half = perm.rounds_f / 2;
+        let mut state = external(state);
+        for rc in &perm.external_constants[..half] {
+            let sboxed: Vec<LinComb<F>> = state.iter().zip(rc).map(|(x, c)| self.pow_lc(&x.clone().c(*c), perm.alpha)).collect();
+            state = external(&sboxed);
+        }
+        for c in &perm.internal_constants {
+            state[0] = self.pow_lc(&state[0].clone().c(*c), perm.alpha);
+            state = internal(&state);
+        }
+        for rc in &perm.external_constants[half..] {
+            let sboxed: Vec<LinComb<F>> = state.iter().zip(rc).map(|(x, c)| self.pow_lc(&x.clone().c(*c), perm.alpha)).collect();
+            state = external(&sboxed);
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_layers_match_matrices() {
+        let p = Poseidon2::goldilocks_t8();
+        let x: Vec<Goldilocks> = (1..=8).map(Goldilocks::from_u64).collect();
+        // circ(2·M4, M4): block i of the output is M4·x_i + sum_j M4·x_j
+        let mut e = x.clone();
+        p.external_linear_layer(&mut e);
+        let m4 = |v: &[Goldilocks]| -> Vec<u64> {
+            M4.iter().map(|r| r.iter().zip(v).map(|(m, x)| m * x.to_canonical_u64()).sum()).collect()
+        };
+        let (a, b) = (m4(&x[..4]), m4(&x[4..]));
+        let want: Vec<Goldilocks> = (0..8).map(|i| Goldilocks::from_u64(2 * [&a, &b][i / 4][i % 4] + [&b, &a][i / 4][i % 4])).collect();
+        assert_eq!(e, want);
+
+        let mut m = x.clone();
+        p.internal_linear_layer(&mut m);
+        assert_eq!(m[3], Goldilocks::from_u64(36) + p.internal_diag[3] * x[3]);
+    }
+
+    fn permute_iota(p: &Poseidon2<Goldilocks>) -> Vec<u64> {
+        let mut state: Vec<Goldilocks> = (0..p.width as u64).map(Goldilocks::from_u64).collect();
+        p.permute(&mut state);
+        state.iter().map(|x| x.to_canonical_u64()).collect()
+    }
+
+    #[test]
+    fn goldilocks_matches_reference() {
+        // perm([0, 1, .., 11]): the published HorizenLabs test vector
+        assert_eq!(permute_iota(&Poseidon2::goldilocks_t12()), [
+            0x01eaef96bdf1c0c1, 0x1f0d2cc525b2540c, 0x6282c1dfe1e0358d, 0xe780d721f698e1e6,
+            0x280c0b6f753d833b, 0x1b942dd5023156ab, 0x43f0df3fcccb8398, 0xe8e8190585489025,
+            0x56bdbf72f77ada22, 0x7911c32bf9dcd705, 0xec467926508fbe67, 0x6a50450ddf85a6ed,
+        ]);
+        // perm([0, 1, .., t - 1]) as computed by the same reference implementation (zkhash 0.2.0)
+        assert_eq!(permute_iota(&Poseidon2::goldilocks_t8()), [
+            0xc5fb1cfe0b4697bb, 0x4a4a32ff849af473, 0xd2fd266077f8efba, 0xf4ad9b74e833916d,
+            0xe6648eb0acc11463, 0x8d5529a930d75194, 0xe8c993aa10da6c90, 0xa73104a95b68031c,
+        ]);
+        assert_eq!(permute_iota(&Poseidon2::goldilocks_t16()), [
+            0x85c54702470d9756, 0xaa53c7a7d52d9898, 0x285128096efb0dd7, 0xf3fde5edd3050ac8,
+            0xc7b65efd040df908, 0x4be3f6c467f57ae9, 0x274e9a67b41754fb, 0x0f7d39cd5de94dac,
+            0xd0224b9794d0b78c, 0x372f6139570042e1, 0xce6e8a93dc4ec26c, 0xace65e30a4daf7af,
+            0x016f2824cc1ba3db, 0x2e8f3af37c434dec, 0xc80831bb6e09da01, 0x3a7d670bf1a86ee8,
+        ]);
+    }
+
+    #[test]
+    fn gadget_matches_native() {
+        for p in [Poseidon2::goldilocks_t8(), Poseidon2::goldilocks_t12(), Poseidon2::goldilocks_t16()] {
+            let xs: Vec<Goldilocks> = (0..p.width as u64).map(|i| Goldilocks::from_u64(i * i + 3)).collect();
+            let mut b = Builder::new();
+            let state: Vec<LinComb<Goldilocks>> = xs.iter().map(|x| LinComb::var(b.alloc(*x))).collect();
+            let out = b.poseidon2_permutation(&p, &state);
+            assert_eq!(b.constraints.len(), 4 * (8 * p.width + 22));
+            let mut native = xs.clone();
+            p.permute(&mut native);
+            assert_eq!(out.iter().map(|lc| b.eval_lc(lc).unwrap()).collect::<Vec<_>>(), native);
+            assert!(crate::verify(&b, &b.witness()));
+        }
+    }
+}
@@ -0,0 +1,269 @@
+// rescue.rs — Rescue-Prime (Szepieniec, Ashur, Dhooghe, "Rescue-Prime: a Standard
+// Specification (SoK)"), native permutation, parameter generation and Builder gadget.
+//
+// Each of the N rounds is
+//   x^alpha, MDS, add constants, x^(1/alpha), MDS, add constants.
+// Parameters follow the reference rescue_prime.sage:
+//   - alpha = smallest integer >= 3 coprime to p - 1, alpha_inv = alpha^(-1) mod p - 1
+//   - N from the Groebner-basis bound, at least 5, plus 50%
+//   - MDS = transpose of the right half of echelon(V), V[i][j] = g^(i*j) (m x 2m), g the
+//     smallest primitive element (the crate's multiplicative generator for the shipped fields)
+//   - constants from SHAKE256("Rescue-XLIX(p,m,capacity,security_level)")
+//
+// In-circuit, the inverse S-box y = x^(1/alpha) is a prover hint checked in the forward
+// direction (y^alpha = x), so both S-box layers cost the same number of constraints.
+
+use crate::field::PrimeField;
+use crate::keccak::shake256;
+use crate::{Builder, Goldilocks, LinComb};
+
+#[derive(Clone, Debug)]
+pub struct RescuePrime<F: PrimeField> {
+    pub width: usize,
+    pub capacity: usize,
+    pub security_level: u32,
+    pub alpha: u64,
+    // alpha^(-1) mod p - 1, little-endian limbs
+    pub alpha_inv: Vec<u64>,
+    pub rounds: usize,
+    pub mds: Vec<Vec<F>>,
+    // 2 * width per round
+    pub round_constants: Vec<F>,
+}
+
+impl<F: PrimeField> RescuePrime<F> {
+    pub fn new(width: usize, capacity: usize, security_level: u32) -> Self {
+        assert!(capacity < width, "capacity must leave a non-empty rate");
+        let p_minus_1 = {
+            let mut e = F::MODULUS.to_vec();
+            e[0] -= 1;
+            e
+        };
+        let alpha = (3u64..).find(|a| gcd(*a, rem_small(&p_minus_1, *a)) == 1).unwrap();
+        // alpha_inv = (k (p - 1) + 1) / alpha for the k in [1, alpha) that makes it exact
+        let r = rem_small(&p_minus_1, alpha);
+        let k = (1..alpha).find(|k| (k * r + 1).is_multiple_of(alpha)).unwrap();
+        let mut alpha_inv = mul_small(&p_minus_1, k);
+        add_small(&mut alpha_inv, 1);
+        let mut alpha_inv = div_small(&alpha_inv, alpha);
+        alpha_inv.truncate(F::MODULUS.len());
+
+        let rounds = num_rounds(width, capacity, security_level, alpha);
+        let mds = vandermonde_mds::<F>(width);
+        let round_constants = round_constants::<F>(width, capacity, security_level, rounds);
+        Self { width, capacity, security_level, alpha, alpha_inv, rounds, mds, round_constants }
+    }
+
+    pub fn mds_mul(&self, state: &[F]) -> Vec<F> {
+        self.mds.iter()
+            .map(|row| row.iter().zip(state).fold(F::ZERO, |acc, (m, x)| acc + *m * *x))
+            .collect()
+    }
+
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width, "state width mismatch");
+        let m = self.width;
+        for r in 0..self.rounds {
+            for x in state.iter_mut() { *x = x.pow(self.alpha); }
+            let mut next = self.mds_mul(state);
+            for (x, c) in next.iter_mut().zip(&self.round_constants[2 * m * r..]) { *x += *c; }
+            for x in next.iter_mut() { *x = x.pow_limbs(&self.alpha_inv); }
+            let mut next = self.mds_mul(&next);
+            for (x, c) in next.iter_mut().zip(&self.round_constants[2 * m * r + m..]) { *x += *c; }
+            state.copy_from_slice(&next);
+        }
+    }
+}
+
+impl RescuePrime<Goldilocks> {
+    // 128-bit security, alpha = 7
+    pub fn goldilocks_m8() -> Self { Self::new(8, 4, 128) }
+    pub fn goldilocks_m12() -> Self { Self::new(12, 4, 128) }
+}
+
+#[cfg(feature = "bn254")]
+impl RescuePrime<crate::bn254::Fr> {
+    // 128-bit security, alpha = 5
+    pub fn bn254_m3() -> Self { Self::new(3, 1, 128) }
+}
+
+// ---- Parameter generation ----
+
+// smallest l1 with binomial(v + dcon, v)^2 > 2^security_level, then ceil(1.5 * max(5, l1))
+fn num_rounds(m: usize, capacity: usize, security_level: u32, alpha: u64) -> usize {
+    let rate = m - capacity;
+    let log2_binomial = |n: usize, k: usize| (1..=k).map(|i| (((n - k + i) as f64) / i as f64).log2()).sum::<f64>();
+    let l1 = (1..25usize)
+        .find(|&n| {
+            let dcon = (0.5 * (alpha - 1) as f64 * m as f64 * (n - 1) as f64 + 2.0).floor() as usize;
+            let v = m * (n - 1) + rate;
+            2.0 * log2_binomial(v + dcon, v) > security_level as f64
+        })
+        .unwrap_or(24);
+    (3 * l1.max(5)).div_ceil(2)
+}
+
+fn vandermonde_mds<F: PrimeField>(m: usize) -> Vec<Vec<F>> {
+    let g = F::multiplicative_generator();
+    let mut v: Vec<Vec<F>> = (0..m)
+        .map(|i| (0..2 * m).map(|j| g.pow((i * j) as u64)).collect())
+        .collect();
+    // reduced row echelon form; the left m x m block is invertible (distinct Vandermonde nodes)
+    for col in 0..m {
+        let pivot = (col..m).find(|&r| !v[r][col].is_zero()).expect("Vandermonde block is singular");
+        v.swap(col, pivot);
+        let inv = v[col][col].inverse().unwrap();
+        for x in v[col].iter_mut() { *x *= inv; }
+        for r in 0..m {
+            if r == col || v[r][col].is_zero() { continue; }
+            let f = v[r][col];
+            let pivot_row = v[col].clone();
+            for (x, y) in v[r].iter_mut().zip(&pivot_row) { *x -= f * *y; }
+        }
+    }
+    (0..m).map(|i| (0..m).map(|j| v[j][m + i]).collect()).collect()
+}
+
+fn round_constants<F: PrimeField>(m: usize, capacity: usize, security_level: u32, rounds: usize) -> Vec<F> {
+    let bytes_per_int = (F::MODULUS_BITS as usize).div_ceil(8) + 1;
+    let count = 2 * m * rounds;
+    let seed = format!("Rescue-XLIX({},{},{},{})", to_decimal(F::MODULUS), m, capacity, security_level);
+    let bytes = shake256(seed.as_bytes(), bytes_per_int * count);
+    let base = F::from_u64(256);
+    bytes.chunks(bytes_per_int)
+        .map(|chunk| chunk.iter().rev().fold(F::ZERO, |acc, b| acc * base + F::from_u64(*b as u64)))
+        .collect()
+}
+
+// ---- small big-integer helpers on little-endian u64 limbs ----
+
+fn gcd(a: u64, b: u64) -> u64 { if b == 0 { a } else { gcd(b, a % b) } }
+
+fn rem_small(x: &[u64], d: u64) -> u64 {
+    x.iter().rev().fold(0u128, |r, l| ((r << 64) | *l as u128) % d as u128) as u64
+}
+
+fn div_small(x: &[u64], d: u64) -> Vec<u64> {
+    let mut out = vec![0u64; x.len()];
+    let mut r = 0u128;
+    for i in (0..x.len()).rev() {
+        let cur = (r << 64) | x[i] as u128;
+        out[i] = (cur / d as u128) as u64;
+        r = cur % d as u128;
+    }
+    out
+}
+
+fn mul_small(x: &[u64], k: u64) -> Vec<u64> {
+    let mut out = Vec::with_capacity(x.len() + 1);
+    let mut carry = 0u128;
+    for l in x {
+        let t = *l as u128 * k as u128 + carry;
+        out.push(t as u64);
+        carry = t >> 64;
+    }
+    out.push(carry as u64);
+    out
+}
+
+fn add_small(x: &mut [u64], k: u64) {
+    let mut carry = k;
+    for l in x.iter_mut() {
+        let (s, o) = l.overflowing_add(carry);
+        *l = s;
+        if !o { return; }
+        carry = 1;
+    }
+}
+
+fn to_decimal(x: &[u64]) -> String {
+    let mut x = x.to_vec();
+    let mut digits = vec![];
+    while x.iter().any(|l| *l != 0) {
+        digits.push(b'0' + rem_small(&x, 10) as u8);
+        x = div_small(&x, 10);
+    }
+    if digits.is_empty() { digits.push(b'0'); }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+// ---- In-circuit gadget ----
+impl<F: PrimeField> Builder<F> {
+    // y = x^(1/alpha) as a hint, enforced by y^(alpha-1) * y = x
+    fn rescue_inverse_sbox(&mut self, perm: &RescuePrime<F>, x: &LinComb<F>) -> LinComb<F> {
+        let y = self.alloc_opt(self.eval_lc(x).map(|v| v.pow_limbs(&perm.alpha_inv)));
+        let y_lc = LinComb::var(y);
+        let y_pow = self.pow_lc(&y_lc, perm.alpha - 1);
+        self.constrain(y_pow, y_lc.clone(), x.clone());
+        y_lc
+    }
+
+    pub fn rescue_permutation(&mut self, perm: &RescuePrime<F>, state: &[LinComb<F>]) -> Vec<LinComb<F>> {
+        assert_eq!(state.len(), perm.width, "state width mismatch");
+        let m = perm.width;
+        let mds = |s: &[LinComb<F>], rc: &[F]| -> Vec<LinComb<F>> {
+            perm.mds.iter().zip(rc)
+                .map(|(row, c)| row.iter().zip(s).fold(LinComb::constant(*c), |acc, (m, x)| acc.add_scaled(x, *m)))
+                .collect()
+        };
+        let mut state = state.to_vec();
+        for r in 0..perm.rounds {
+            let fwd: Vec<LinComb<F>> = state.iter().map(|x| self.pow_lc(x, perm.alpha)).collect();
+            let mid = mds(&fwd, &perm.round_constants[2 * m * r..]);
+            let inv: Vec<LinComb<F>> = mid.iter().map(|x| self.rescue_inverse_sbox(perm, x)).collect();
+            state = mds(&inv, &perm.round_constants[2 * m * r + m..]);
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // expected outputs computed with the reference rescue_prime.sage algorithm on input [0, 1, .., m-1]
+    #[test]
+    fn goldilocks_m12_vector() {
+        let p = RescuePrime::goldilocks_m12();
+        assert_eq!((p.alpha, p.alpha_inv.as_slice(), p.rounds), (7, &[10540996611094048183u64][..], 8));
+        let mut state: Vec<Goldilocks> = (0..12).map(Goldilocks::from_u64).collect();
+        p.permute(&mut state);
+        let want: [u64; 12] = [
+            0xccd94518a9af0782, 0xf7ae608ea3308620, 0xf56dd53fae1f5876, 0x11e7b12aedd8ca86,
+            0x869f9c3f93cd5630, 0x6ffe37312e58ac20, 0xac42b1f88aa27570, 0x312f6b96f7611c8a,
+            0xf8b19bd51a741b7e, 0x9d1c158cfa1b7a12, 0x62ae69ae877e1e51, 0xce62641553ffe1bc,
+        ];
+        assert_eq!(state, want.map(Goldilocks::from_u64));
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn bn254_m3_vector() {
+        use crate::bn254::Fr;
+        let p = RescuePrime::<Fr>::bn254_m3();
+        assert_eq!((p.alpha, p.rounds), (5, 14));
+        let mut state = [0, 1, 2].map(Fr::from_u64);
+        p.permute(&mut state);
+        assert_eq!(state.map(|x| x.to_string()), [
+            "0x0dc30ccd5d64e5bea071e99087ef86d433eb156aa0500a823298f9bb05328bd2",
+            "0x189893368d5815608c56e44cc67f7e821e093bb6254a0553f9ff69f4d99debc8",
+            "0x1acafc768221448ebc51fa2cd1e3c9b2044a0c04f3509d833b0a82c7e3462610",
+        ]);
+    }
+
+    #[test]
+    fn gadget_matches_native() {
+        let p = RescuePrime::goldilocks_m8();
+        let xs: Vec<Goldilocks> = (0..8).map(|i| Goldilocks::from_u64(3 * i + 1)).collect();
+        let mut b = Builder::new();
+        let state: Vec<LinComb<Goldilocks>> = xs.iter().map(|x| LinComb::var(b.alloc(*x))).collect();
+        let out = b.rescue_permutation(&p, &state);
+        // 4 constraints per forward and per inverse S-box
+        assert_eq!(b.constraints.len(), p.rounds * 2 * 8 * 4);
+        let mut native = xs.clone();
+        p.permute(&mut native);
+        assert_eq!(out.iter().map(|lc| b.eval_lc(lc).unwrap()).collect::<Vec<_>>(), native);
+        assert!(crate::verify(&b, &b.witness()));
+    }
+}
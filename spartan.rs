@@ -0,0 +1,414 @@
+// spartan.rs — a Spartan-style transparent SNARK (Setty 2020) for `Builder` circuits,
+// generic over the multilinear commitment scheme.
+//
+// Layout: z has 2^s entries; the first half holds the witness variables (index order), the
+// second half ONE followed by `public_inputs`, padded with zeros. Constant terms of the
+// constraints are moved onto ONE. With A, B, C the 2^t x 2^s constraint matrices:
+//
+//   outer sumcheck  0 = Σ_x eq(τ, x)·(Ãz(x)·B̃z(x) - C̃z(x))                      -> r_x
+//   inner sumcheck  r_A·v_A + r_B·v_B + r_C·v_C = Σ_y (r_A·Ã + r_B·B̃ + r_C·C̃)(r_x, y)·z̃(y)  -> r_y
+//
+// and z̃(r_y) = (1 - r_y[0])·w̃(r_y[1..]) + r_y[0]·ĩo(r_y[1..]), where only w̃ is committed.
+// The verifier evaluates the matrix MLEs at (r_x, r_y) itself in O(non-zeros) — there is no
+// SPARK sparse-polynomial commitment — and the protocol is not zero-knowledge.
+//
+// `prove_relaxed` / `verify_relaxed` handle Nova's relaxed relation Az∘Bz = u·Cz + E, with
+// u in place of ONE: the outer sumcheck runs over eq(τ, x)·(Ãz·B̃z - u·C̃z - Ẽ) and Ẽ(r_x)
+// is opened against a commitment to E. W and E are committed by the caller (they are part
+// of the relaxed instance), so the proof carries openings only.
+
+use crate::field::PrimeField;
+use crate::multilinear::{eq_eval, sumcheck_prove, sumcheck_verify, MultilinearPolynomial, SumcheckError, SumcheckProof};
+use crate::pcs::MultilinearPcs;
+use crate::transcript::Transcript;
+use crate::{try_verify, Builder, VerifyError};
+use std::fmt;
+
+// sparse R1CS in Spartan's column layout
+#[derive(Clone, Debug)]
+pub struct R1cs<F: PrimeField> {
+    pub log_rows: usize,
+    pub log_cols: usize,
+    // builder variable -> column
+    pub column: Vec<usize>,
+    pub num_public: usize,
+    // (row, column, value)
+    pub a: Vec<(usize, usize, F)>,
+    pub b: Vec<(usize, usize, F)>,
+    pub c: Vec<(usize, usize, F)>,
+}
+
+impl<F: PrimeField> R1cs<F> {
+    pub fn new(builder: &Builder<F>) -> Self {
+        let mut instance = vec![Builder::<F>::ONE];
+        instance.extend(&builder.public_inputs);
+        let witness: Vec<usize> = (0..builder.next_var).filter(|v| !instance.contains(v)).collect();
+        let half = witness.len().max(instance.len()).next_power_of_two();
+        let mut column = vec![0; builder.next_var];
+        for (i, v) in witness.iter().enumerate() { column[*v] = i; }
+        for (i, v) in instance.iter().enumerate() { column[*v] = half + i; }
+
+        let mut mats = [vec![], vec![], vec![]];
+        for (row, con) in builder.constraints.iter().enumerate() {
+            for (m, lc) in mats.iter_mut().zip([&con.a, &con.b, &con.c]) {
+                let lc = lc.clone().t(Builder::<F>::ONE, lc.const_term).simplify();
+                m.extend(lc.terms.iter().map(|(v, val)| (row, column[*v], *val)));
+            }
+        }
+        let [a, b, c] = mats;
+        Self {
+            log_rows: builder.constraints.len().max(1).next_power_of_two().trailing_zeros() as usize,
+            log_cols: (2 * half).trailing_zeros() as usize,
+            column,
+            num_public: builder.public_inputs.len(),
+            a, b, c,
+        }
+    }
+
+    fn half(&self) -> usize { 1 << (self.log_cols - 1) }
+
+    fn mat_vec(&self, m: &[(usize, usize, F)], z: &[F]) -> Vec<F> {
+        let mut out = vec![F::ZERO; 1 << self.log_rows];
+        for (r, c, v) in m { out[*r] += *v * z[*c]; }
+        out
+    }
+
+    // ĩo: ONE (u for relaxed instances), then the public inputs, zero-padded to half the columns
+    fn io_poly(&self, u: F, public: &[F]) -> MultilinearPolynomial<F> {
+        let mut io = vec![u];
+        io.extend(public);
+        io.resize(self.half(), F::ZERO);
+        MultilinearPolynomial::new(io)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpartanProof<F: PrimeField, P: MultilinearPcs<F>> {
+    pub witness_commitment: P::Commitment,
+    pub outer: SumcheckProof<F>,
+    // Ãz(r_x), B̃z(r_x), C̃z(r_x)
+    pub claims: [F; 3],
+    pub inner: SumcheckProof<F>,
+    // w̃(r_y[1..]) and its opening
+    pub witness_eval: F,
+    pub opening: P::Opening,
+}
+
+#[derive(Clone, Debug)]
+pub struct RelaxedSpartanProof<F: PrimeField, P: MultilinearPcs<F>> {
+    pub outer: SumcheckProof<F>,
+    pub claims: [F; 3],
+    // Ẽ(r_x) and its opening
+    pub error_eval: F,
+    pub error_opening: P::Opening,
+    pub inner: SumcheckProof<F>,
+    pub witness_eval: F,
+    pub opening: P::Opening,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpartanError {
+    Witness(VerifyError),
+    PublicInputCount { expected: usize, got: usize },
+    Sumcheck(SumcheckError),
+    // final outer / inner sumcheck claim does not match the evaluations
+    OuterClaim,
+    InnerClaim,
+    Opening,
+}
+
+impl fmt::Display for SpartanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpartanError::Witness(e) => write!(f, "cannot prove: {e}"),
+            SpartanError::PublicInputCount { expected, got } => write!(f, "expected {expected} public inputs, got {got}"),
+            SpartanError::Sumcheck(e) => write!(f, "sumcheck: {e}"),
+            SpartanError::OuterClaim => write!(f, "outer sumcheck claim does not match Az·Bz - Cz"),
+            SpartanError::InnerClaim => write!(f, "inner sumcheck claim does not match the matrix and witness evaluations"),
+            SpartanError::Opening => write!(f, "witness commitment opening is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for SpartanError {}
+
+fn start_transcript<F: PrimeField>(r1cs: &R1cs<F>, public: &[F]) -> Transcript {
+    let mut t = Transcript::new(b"delta_zk spartan");
+    t.append_bytes(b"shape", &[r1cs.log_rows as u8, r1cs.log_cols as u8]);
+    t.append_fields(b"public inputs", public);
+    t
+}
+
+// the sumchecks shared by the strict (u = 1, no E) and relaxed provers
+struct Sumchecks<F: PrimeField> {
+    outer: SumcheckProof<F>,
+    claims: [F; 3],
+    error_eval: Option<F>,
+    inner: SumcheckProof<F>,
+    r_x: Vec<F>,
+    r_y: Vec<F>,
+}
+
+fn prove_sumchecks<F: PrimeField>(r1cs: &R1cs<F>, z: Vec<F>, u: F, error: Option<&MultilinearPolynomial<F>>, t: &mut Transcript) -> Sumchecks<F> {
+    // outer sumcheck
+    let tau: Vec<F> = t.challenges(b"tau", r1cs.log_rows);
+    let [az, bz, cz] = [&r1cs.a, &r1cs.b, &r1cs.c].map(|m| MultilinearPolynomial::new(r1cs.mat_vec(m, &z)));
+    let mut polys = vec![MultilinearPolynomial::eq(&tau), az, bz, cz];
+    polys.extend(error.cloned());
+    let (outer, r_x, finals) = sumcheck_prove(
+        &polys, 3,
+        |v| v[0] * (v[1] * v[2] - u * v[3] - v.get(4).copied().unwrap_or(F::ZERO)), t,
+    );
+    let claims = [finals[1], finals[2], finals[3]];
+    t.append_fields(b"claims", &claims);
+    let error_eval = finals.get(4).copied();
+    if let Some(e) = &error_eval { t.append_field(b"error eval", e); }
+
+    // inner sumcheck over y with M(y) = Σ r_M·M̃(r_x, y)
+    let r: Vec<F> = t.challenges(b"inner combination", 3);
+    let eq_rx = MultilinearPolynomial::eq(&r_x).evals;
+    let mut m = vec![F::ZERO; 1 << r1cs.log_cols];
+    for (mat, rm) in [&r1cs.a, &r1cs.b, &r1cs.c].iter().zip(&r) {
+        for (row, col, val) in mat.iter() { m[*col] += *rm * *val * eq_rx[*row]; }
+    }
+    let (inner, r_y, _) = sumcheck_prove(
+        &[MultilinearPolynomial::new(m), MultilinearPolynomial::new(z)], 2,
+        |v| v[0] * v[1], t,
+    );
+    Sumchecks { outer, claims, error_eval, inner, r_x, r_y }
+}
+
+// the sumcheck part of a strict or relaxed proof, as seen by the verifier
+struct SumcheckClaims<'a, F: PrimeField> {
+    outer: &'a SumcheckProof<F>,
+    claims: [F; 3],
+    error_eval: Option<F>,
+    inner: &'a SumcheckProof<F>,
+    witness_eval: F,
+}
+
+// checks both sumchecks for z = (w, u, public); returns (r_x, r_y)
+fn verify_sumchecks<F: PrimeField>(r1cs: &R1cs<F>, u: F, public: &[F], p: SumcheckClaims<F>, t: &mut Transcript) -> Result<(Vec<F>, Vec<F>), SpartanError> {
+    let tau: Vec<F> = t.challenges(b"tau", r1cs.log_rows);
+    let (r_x, outer_claim) = sumcheck_verify(F::ZERO, r1cs.log_rows, 3, p.outer, t).map_err(SpartanError::Sumcheck)?;
+    let [va, vb, vc] = p.claims;
+    if eq_eval(&tau, &r_x) * (va * vb - u * vc - p.error_eval.unwrap_or(F::ZERO)) != outer_claim {
+        return Err(SpartanError::OuterClaim);
+    }
+    t.append_fields(b"claims", &p.claims);
+    if let Some(e) = &p.error_eval { t.append_field(b"error eval", e); }
+
+    let r: Vec<F> = t.challenges(b"inner combination", 3);
+    let claim = r[0] * va + r[1] * vb + r[2] * vc;
+    let (r_y, inner_claim) = sumcheck_verify(claim, r1cs.log_cols, 2, p.inner, t).map_err(SpartanError::Sumcheck)?;
+    let (eq_rx, eq_ry) = (MultilinearPolynomial::eq(&r_x).evals, MultilinearPolynomial::eq(&r_y).evals);
+    let m_eval = [&r1cs.a, &r1cs.b, &r1cs.c].iter().zip(&r).fold(F::ZERO, |acc, (mat, rm)| {
+        acc + *rm * mat.iter().fold(F::ZERO, |s, (row, col, val)| s + *val * eq_rx[*row] * eq_ry[*col])
+    });
+    let io_eval = r1cs.io_poly(u, public).evaluate(&r_y[1..]);
+    let z_eval = (F::ONE - r_y[0]) * p.witness_eval + r_y[0] * io_eval;
+    if m_eval * z_eval != inner_claim { return Err(SpartanError::InnerClaim); }
+    Ok((r_x, r_y))
+}
+
+pub fn prove<F: PrimeField, P: MultilinearPcs<F>>(pcs: &P, r1cs: &R1cs<F>, builder: &Builder<F>) -> Result<SpartanProof<F, P>, SpartanError> {
+    try_verify(builder, &builder.witness()).map_err(SpartanError::Witness)?;
+    // every variable gets a column, including ones no constraint reads
+    let missing: Vec<usize> = (0..r1cs.column.len()).filter(|v| builder.value(*v).is_none()).collect();
+    if !missing.is_empty() {
+        return Err(SpartanError::Witness(VerifyError::Unassigned(missing)));
+    }
+    let mut z = vec![F::ZERO; 1 << r1cs.log_cols];
+    for (v, col) in r1cs.column.iter().enumerate() {
+        z[*col] = builder.value(v).unwrap();
+    }
+    let public = builder.public_values().unwrap();
+    let mut t = start_transcript(r1cs, &public);
+    let w = MultilinearPolynomial::new(z[..r1cs.half()].to_vec());
+    let witness_commitment = pcs.commit(&w);
+    pcs.append_commitment(&witness_commitment, &mut t);
+
+    let sc = prove_sumchecks(r1cs, z, F::ONE, None, &mut t);
+    let witness_eval = w.evaluate(&sc.r_y[1..]);
+    t.append_field(b"witness eval", &witness_eval);
+    let opening = pcs.open(&w, &sc.r_y[1..], &mut t);
+    Ok(SpartanProof { witness_commitment, outer: sc.outer, claims: sc.claims, inner: sc.inner, witness_eval, opening })
+}
+
+pub fn verify<F: PrimeField, P: MultilinearPcs<F>>(pcs: &P, r1cs: &R1cs<F>, public: &[F], proof: &SpartanProof<F, P>) -> Result<(), SpartanError> {
+    if public.len() != r1cs.num_public {
+        return Err(SpartanError::PublicInputCount { expected: r1cs.num_public, got: public.len() });
+    }
+    let mut t = start_transcript(r1cs, public);
+    pcs.append_commitment(&proof.witness_commitment, &mut t);
+
+    let claims = SumcheckClaims { outer: &proof.outer, claims: proof.claims, error_eval: None, inner: &proof.inner, witness_eval: proof.witness_eval };
+    let (_, r_y) = verify_sumchecks(r1cs, F::ONE, public, claims, &mut t)?;
+    t.append_field(b"witness eval", &proof.witness_eval);
+    if !pcs.verify(&proof.witness_commitment, &r_y[1..], proof.witness_eval, &proof.opening, &mut t) {
+        return Err(SpartanError::Opening);
+    }
+    Ok(())
+}
+
+fn start_relaxed_transcript<F: PrimeField, P: MultilinearPcs<F>>(
+    pcs: &P, r1cs: &R1cs<F>, witness_commitment: &P::Commitment, error_commitment: &P::Commitment, u: F, public: &[F],
+) -> Transcript {
+    let mut t = start_transcript(r1cs, public);
+    t.append_field(b"u", &u);
+    pcs.append_commitment(witness_commitment, &mut t);
+    pcs.append_commitment(error_commitment, &mut t);
+    t
+}
+
+// Proof for the relaxed instance (commit(W), commit(E), u, public). `w` holds the witness
+// columns and `error` one entry per constraint; both are zero-padded here.
+pub fn prove_relaxed<F: PrimeField, P: MultilinearPcs<F>>(
+    pcs: &P, r1cs: &R1cs<F>, w: &[F], u: F, public: &[F], error: &[F],
+) -> Result<RelaxedSpartanProof<F, P>, SpartanError> {
+    if public.len() != r1cs.num_public {
+        return Err(SpartanError::PublicInputCount { expected: r1cs.num_public, got: public.len() });
+    }
+    assert!(w.len() <= r1cs.half() && error.len() <= 1 << r1cs.log_rows, "relaxed witness does not fit the shape");
+    let mut z = w.to_vec();
+    z.resize(r1cs.half(), F::ZERO);
+    let w = MultilinearPolynomial::new(z.clone());
+    z.extend(r1cs.io_poly(u, public).evals);
+    let mut e = error.to_vec();
+    e.resize(1 << r1cs.log_rows, F::ZERO);
+    let e = MultilinearPolynomial::new(e);
+    // Az∘Bz - u·Cz must equal E row by row
+    let [az, bz, cz] = [&r1cs.a, &r1cs.b, &r1cs.c].map(|m| r1cs.mat_vec(m, &z));
+    if let Some(i) = (0..e.evals.len()).position(|i| az[i] * bz[i] - u * cz[i] != e.evals[i]) {
+        return Err(SpartanError::Witness(VerifyError::Unsatisfied(i)));
+    }
+
+    let mut t = start_relaxed_transcript(pcs, r1cs, &pcs.commit(&w), &pcs.commit(&e), u, public);
+    let sc = prove_sumchecks(r1cs, z, u, Some(&e), &mut t);
+    let witness_eval = w.evaluate(&sc.r_y[1..]);
+    t.append_field(b"witness eval", &witness_eval);
+    let error_opening = pcs.open(&e, &sc.r_x, &mut t);
+    let opening = pcs.open(&w, &sc.r_y[1..], &mut t);
+    Ok(RelaxedSpartanProof {
+        outer: sc.outer, claims: sc.claims, error_eval: sc.error_eval.unwrap(), error_opening,
+        inner: sc.inner, witness_eval, opening,
+    })
+}
+
+pub fn verify_relaxed<F: PrimeField, P: MultilinearPcs<F>>(
+    pcs: &P, r1cs: &R1cs<F>, witness_commitment: &P::Commitment, error_commitment: &P::Commitment,
+    u: F, public: &[F], proof: &RelaxedSpartanProof<F, P>,
+) -> Result<(), SpartanError> {
+    if public.len() != r1cs.num_public {
+        return Err(SpartanError::PublicInputCount { expected: r1cs.num_public, got: public.len() });
+    }
+    let mut t = start_relaxed_transcript(pcs, r1cs, witness_commitment, error_commitment, u, public);
+    let claims = SumcheckClaims {
+        outer: &proof.outer, claims: proof.claims, error_eval: Some(proof.error_eval), inner: &proof.inner, witness_eval: proof.witness_eval,
+    };
+    let (r_x, r_y) = verify_sumchecks(r1cs, u, public, claims, &mut t)?;
+    t.append_field(b"witness eval", &proof.witness_eval);
+    if !pcs.verify(error_commitment, &r_x, proof.error_eval, &proof.error_opening, &mut t)
+        || !pcs.verify(witness_commitment, &r_y[1..], proof.witness_eval, &proof.opening, &mut t) {
+        return Err(SpartanError::Opening);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::MerkleTree;
+    use crate::pcs::MerklePcs;
+    use crate::poseidon::Poseidon;
+    use crate::Goldilocks;
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+
+    // Merkle membership of leaf 5 in a depth-3 tree, root public
+    fn membership(perm: &Poseidon<Goldilocks>, leaf: Goldilocks) -> (Builder<Goldilocks>, Goldilocks) {
+        let leaves: Vec<Goldilocks> = (0..8).map(|i| f(i * 3 + 1)).collect();
+        let tree = MerkleTree::new(perm.clone(), 3, &leaves);
+        let path = tree.path(5);
+        let mut b = Builder::new();
+        let root = b.alloc_public(tree.root());
+        let leaf = b.alloc(leaf);
+        let sibs: Vec<usize> = path.siblings.iter().map(|s| b.alloc(*s)).collect();
+        let dirs: Vec<usize> = path.directions().iter().map(|d| b.alloc_bit(*d)).collect();
+        b.merkle_membership(perm, leaf, &sibs, &dirs, root);
+        (b, tree.root())
+    }
+
+    #[test]
+    fn proves_merkle_membership() {
+        let perm = Poseidon::goldilocks_t8();
+        let pcs = MerklePcs::new(perm.clone());
+        let (b, root) = membership(&perm, f(16));
+        let r1cs = R1cs::new(&b);
+        let proof = prove(&pcs, &r1cs, &b).unwrap();
+        assert_eq!(verify(&pcs, &r1cs, &[root], &proof), Ok(()));
+        assert!(matches!(verify(&pcs, &r1cs, &[root + f(1)], &proof), Err(SpartanError::Sumcheck(SumcheckError::RoundSum { .. }))));
+        assert!(matches!(verify(&pcs, &r1cs, &[], &proof), Err(SpartanError::PublicInputCount { .. })));
+
+        let mut bad = proof.clone();
+        bad.claims[2] += f(1);
+        assert_eq!(verify(&pcs, &r1cs, &[root], &bad), Err(SpartanError::OuterClaim));
+        let mut bad = proof.clone();
+        bad.witness_eval += f(1);
+        assert_eq!(verify(&pcs, &r1cs, &[root], &bad), Err(SpartanError::InnerClaim));
+
+        // a wrong leaf does not satisfy the circuit, so there is nothing to prove
+        let (b, _) = membership(&perm, f(17));
+        assert!(matches!(prove(&pcs, &r1cs, &b), Err(SpartanError::Witness(_))));
+    }
+
+    #[test]
+    fn small_circuit_layout() {
+        // x * y = out, x + y = 7; out public
+        let mut b = Builder::new();
+        let (x, y) = (b.alloc(f(3)), b.alloc(f(4)));
+        let out = b.alloc_public(f(12));
+        b.mul_gate(x, y, out);
+        b.constrain(crate::LinComb::var(x).t(y, f(1)), crate::LinComb::constant(f(1)), crate::LinComb::constant(f(7)));
+        let r1cs = R1cs::new(&b);
+        assert_eq!((r1cs.log_rows, r1cs.log_cols, r1cs.column.clone()), (1, 2, vec![2, 0, 1, 3]));
+        let pcs = MerklePcs::new(Poseidon::goldilocks_t8());
+        let proof = prove(&pcs, &r1cs, &b).unwrap();
+        assert_eq!(verify(&pcs, &r1cs, &[f(12)], &proof), Ok(()));
+
+        // an unassigned variable that no constraint reads still needs a value in z
+        let extra = b.alloc_opt(None);
+        let r1cs = R1cs::new(&b);
+        assert_eq!(prove(&pcs, &r1cs, &b).err(), Some(SpartanError::Witness(VerifyError::Unassigned(vec![extra]))));
+    }
+
+    #[test]
+    fn relaxed_instances() {
+        let mut b = Builder::new();
+        let (x, y) = (b.alloc(f(3)), b.alloc(f(4)));
+        let out = b.alloc_public(f(12));
+        b.mul_gate(x, y, out);
+        b.constrain(crate::LinComb::var(x).t(y, f(1)), crate::LinComb::constant(f(1)), crate::LinComb::constant(f(7)));
+        let r1cs = R1cs::new(&b);
+        let pcs = MerklePcs::new(Poseidon::goldilocks_t8());
+
+        // any (W, u, x) is satisfied by E = Az∘Bz - u·Cz
+        let (w, u, public) = ([f(5), f(9)], f(3), [f(100)]);
+        let mut z = w.to_vec();
+        z.extend(r1cs.io_poly(u, &public).evals);
+        let [az, bz, cz] = [&r1cs.a, &r1cs.b, &r1cs.c].map(|m| r1cs.mat_vec(m, &z));
+        let e: Vec<Goldilocks> = (0..2).map(|i| az[i] * bz[i] - u * cz[i]).collect();
+        assert!(e.iter().all(|x| !x.is_zero()));
+        let (cw, ce) = (pcs.commit(&MultilinearPolynomial::new(w.to_vec())), pcs.commit(&MultilinearPolynomial::new(e.clone())));
+        let proof = prove_relaxed(&pcs, &r1cs, &w, u, &public, &e).unwrap();
+        assert_eq!(verify_relaxed(&pcs, &r1cs, &cw, &ce, u, &public, &proof), Ok(()));
+
+        assert!(verify_relaxed(&pcs, &r1cs, &cw, &ce, u + f(1), &public, &proof).is_err());
+        assert!(verify_relaxed(&pcs, &r1cs, &cw, &cw, u, &public, &proof).is_err());
+        let mut bad = proof.clone();
+        bad.error_eval += f(1);
+        assert_eq!(verify_relaxed(&pcs, &r1cs, &cw, &ce, u, &public, &bad), Err(SpartanError::OuterClaim));
+        assert_eq!(prove_relaxed(&pcs, &r1cs, &w, u, &public, &[e[0], e[1] + f(1)]).err(),
+                   Some(SpartanError::Witness(VerifyError::Unsatisfied(1))));
+    }
+}
@@ -0,0 +1,314 @@
+// stark.rs — a small STARK for `Air`s, with FRI (fri.rs) as the low-degree test.
+//
+// With T trace rows, H = <ω_T> and D the largest transition degree, the trace columns are
+// interpolated over H and extended to the FRI domain g·<ω_N>, N = q·blowup, where
+// q = T·next_pow2(D - 1) bounds the degree of the composition polynomial
+//
+//   Q(x) = Σ_i α_i·C_i(t(x), t(ω_T·x))·(x - ω_T^(T-1)) / (x^T - 1)
+//        + Σ_j β_j·(t_{c_j}(x) - v_j) / (x - ω_T^(r_j)),
+//
+// which is a polynomial exactly when every transition C_i holds on rows 0..T-1 and every
+// boundary t_{c_j}(ω^(r_j)) = v_j holds. The trace rows and Q are Merkle-committed, the
+// prover reveals t(z), t(ω_T·z) and Q(z) at an out-of-domain point z, and FRI checks that
+//
+//   P(x) = Σ_c γ_c·(t_c(x) - t_c(z)) / (x - z) + γ'_c·(t_c(x) - t_c(ω_T·z)) / (x - ω_T·z)
+//        + γ_Q·(Q(x) - Q(z)) / (x - z)
+//
+// has degree < q; at every FRI query the verifier recomputes P from the opened trace row
+// and Q value. The trace stays in the base field F, while z, α, β and γ are drawn from an
+// extension E (GoldilocksExt2 / Ext3 over Goldilocks), so Q, P and the FRI layers are
+// E-valued; z is redrawn until it avoids H and the FRI domain, where Q and P have poles.
+// Commitments use the wide digests of fri.rs. The proof is not zero-knowledge (no trace
+// masking).
+
+use crate::air::{Air, AirError, TraceTable};
+use crate::field::{ExtensionField, PrimeField};
+use crate::fri::{self, coset_intt_ext, evaluate, leaf_hash, FriConfig, FriError, FriProof, QueryLayer};
+use crate::merkle::{DigestPath, DigestTree};
+use crate::ntt::{intt, lde_from_coeffs, root_of_unity};
+use crate::poseidon::{Digest, Poseidon};
+use crate::transcript::Transcript;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StarkProof<F: PrimeField, E: ExtensionField<F> = F> {
+    pub trace_root: Digest<F>,
+    pub composition_root: Digest<F>,
+    pub trace_at_z: Vec<E>,
+    pub trace_at_next_z: Vec<E>,
+    pub composition_at_z: E,
+    pub fri: FriProof<F, E>,
+    // per FRI query: the trace row and the composition value at its position
+    pub trace_openings: Vec<QueryLayer<F>>,
+    pub composition_openings: Vec<QueryLayer<F, E>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StarkError {
+    Air(AirError),
+    Shape,
+    // Q(z) does not match the constraints evaluated at z
+    OutOfDomain,
+    Fri(FriError),
+    Merkle { query: usize },
+    // the FRI codeword disagrees with P recomputed from the openings
+    Deep { query: usize },
+}
+
+impl fmt::Display for StarkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StarkError::Air(e) => write!(f, "trace does not satisfy the AIR: {e}"),
+            StarkError::Shape => write!(f, "proof does not match the AIR"),
+            StarkError::OutOfDomain => write!(f, "composition polynomial disagrees with the constraints at z"),
+            StarkError::Fri(e) => write!(f, "FRI: {e}"),
+            StarkError::Merkle { query } => write!(f, "query {query}: bad trace or composition opening"),
+            StarkError::Deep { query } => write!(f, "query {query}: FRI codeword does not match the openings"),
+        }
+    }
+}
+
+impl std::error::Error for StarkError {}
+
+// degree bound of Q (and of the FRI input)
+fn composition_bound<F: PrimeField>(air: &Air<F>) -> usize {
+    air.num_rows * (air.max_degree() - 1).max(1).next_power_of_two()
+}
+
+fn start_transcript<F: PrimeField>(air: &Air<F>) -> Transcript {
+    let mut t = Transcript::new(b"delta_zk stark");
+    t.append_bytes(b"shape", &[air.width as u64, air.num_rows as u64, air.transitions.len() as u64].map(u64::to_le_bytes).concat());
+    let boundaries: Vec<F> = air.boundaries.iter()
+        .flat_map(|b| [F::from_u64(b.column as u64), F::from_u64(b.row as u64), b.value]).collect();
+    t.append_fields(b"boundaries", &boundaries);
+    t
+}
+
+fn lift<F: PrimeField, E: ExtensionField<F>>(xs: &[F]) -> Vec<E> { xs.iter().map(|x| E::from_base(*x)).collect() }
+
+// Q at x from the trace values at x and ω_T·x; None if x is in the trace domain
+fn composition_at<F: PrimeField, E: ExtensionField<F>>(air: &Air<F>, alphas: &[E], cur: &[E], next: &[E], x: E) -> Option<E> {
+    let omega = root_of_unity::<F>(air.num_rows.trailing_zeros());
+    let last = omega.pow(air.num_rows as u64 - 1);
+    let (betas, alphas) = (&alphas[air.transitions.len()..], &alphas[..air.transitions.len()]);
+    let z_inv = (x - E::from_base(last)) * (x.exp(air.num_rows as u64) - E::from_base(F::ONE)).invert()?;
+    let transitions = air.transitions.iter().zip(alphas).fold(E::from_base(F::ZERO), |acc, (e, a)| acc + *a * e.eval(cur, next));
+    air.boundaries.iter().zip(betas).try_fold(transitions * z_inv, |acc, (b, beta)| {
+        Some(acc + *beta * (cur[b.column] - E::from_base(b.value)) * (x - E::from_base(omega.pow(b.row as u64))).invert()?)
+    })
+}
+
+// P at x from the trace row and Q value at x; None if x is z or ω_T·z
+fn deep_at<F: PrimeField, E: ExtensionField<F>>(proof: &StarkProof<F, E>, gammas: &[E], row: &[F], q: E, x: F, z: E, next_z: E) -> Option<E> {
+    let x = E::from_base(x);
+    let (dz, dnz) = ((x - z).invert()?, (x - next_z).invert()?);
+    let w = row.len();
+    let trace = (0..w).fold(E::from_base(F::ZERO), |acc, c| {
+        let v = E::from_base(row[c]);
+        acc + gammas[c] * (v - proof.trace_at_z[c]) * dz + gammas[w + c] * (v - proof.trace_at_next_z[c]) * dnz
+    });
+    Some(trace + gammas[2 * w] * (q - proof.composition_at_z) * dz)
+}
+
+fn domain_point<F: PrimeField>(n: usize, i: usize) -> F {
+    F::multiplicative_generator() * root_of_unity::<F>(n.trailing_zeros()).pow(i as u64)
+}
+
+// z with z^T != 1 and (z/g)^N != 1: off the trace domain and the FRI domain (then so is
+// ω_T·z). A draw is rejected with probability about (T + N)/|E|, so this rarely loops.
+fn ood_point<F: PrimeField, E: ExtensionField<F>>(t: &mut Transcript, rows: usize, n: usize) -> E {
+    let one = E::from_base(F::ONE);
+    let g_inv = E::from_base(F::multiplicative_generator().inverse().unwrap());
+    loop {
+        let z: E = t.challenge_ext(b"ood point");
+        if z.exp(rows as u64) != one && (z * g_inv).exp(n as u64) != one {
+            return z;
+        }
+    }
+}
+
+pub fn prove<F: PrimeField, E: ExtensionField<F>>(config: &FriConfig, perm: &Poseidon<F>, air: &Air<F>, trace: &TraceTable<F>) -> Result<StarkProof<F, E>, StarkError> {
+    air.check_trace(trace).map_err(StarkError::Air)?;
+    let (rows, q_bound) = (air.num_rows, composition_bound(air));
+    let n = q_bound * config.blowup();
+    let step = n / rows;
+    let mut t = start_transcript(air);
+
+    // trace LDE, committed row-wise
+    let coeffs: Vec<Vec<F>> = trace.columns.iter().map(|c| {
+        let mut c = c.clone();
+        intt(&mut c);
+        c
+    }).collect();
+    let lde: Vec<Vec<F>> = coeffs.iter().map(|c| lde_from_coeffs(c, n / rows, F::multiplicative_generator())).collect();
+    let lde_row = |i: usize| -> Vec<F> { lde.iter().map(|c| c[i]).collect() };
+    let trace_tree = DigestTree::new(perm, (0..n).map(|i| leaf_hash(perm, &lde_row(i))).collect());
+    t.append_fields(b"trace root", &trace_tree.root());
+
+    // composition
+    let alphas: Vec<E> = t.challenges_ext(b"constraint coefficients", air.transitions.len() + air.boundaries.len());
+    let composition: Vec<E> = (0..n).map(|i| {
+        let x = E::from_base(domain_point(n, i));
+        composition_at(air, &alphas, &lift(&lde_row(i)), &lift(&lde_row((i + step) % n)), x)
+            .expect("the FRI domain is disjoint from the trace domain")
+    }).collect();
+    let comp_tree = DigestTree::new(perm, composition.iter().map(|q| leaf_hash(perm, &[*q])).collect());
+    t.append_fields(b"composition root", &comp_tree.root());
+
+    // out-of-domain evaluations
+    let z: E = ood_point(&mut t, rows, n);
+    let next_z = z * E::from_base(root_of_unity::<F>(rows.trailing_zeros()));
+    let comp_coeffs = coset_intt_ext(&composition, F::multiplicative_generator());
+    let mut proof = StarkProof {
+        trace_root: trace_tree.root(),
+        composition_root: comp_tree.root(),
+        trace_at_z: coeffs.iter().map(|c| evaluate(&lift(c), z)).collect(),
+        trace_at_next_z: coeffs.iter().map(|c| evaluate(&lift(c), next_z)).collect(),
+        composition_at_z: evaluate(&comp_coeffs, z),
+        fri: FriProof { layer_roots: vec![], final_poly: vec![], pow_nonce: 0, queries: vec![] },
+        trace_openings: vec![],
+        composition_openings: vec![],
+    };
+    append_ood(&mut t, &proof);
+
+    // DEEP composition and FRI
+    let gammas: Vec<E> = t.challenges_ext(b"deep coefficients", 2 * air.width + 1);
+    let deep: Vec<E> = (0..n).map(|i| {
+        deep_at(&proof, &gammas, &lde_row(i), composition[i], domain_point(n, i), z, next_z).expect("z is outside the FRI domain")
+    }).collect();
+    let (fri_proof, positions) = fri::prove(config, perm, &deep, &mut t);
+    proof.fri = fri_proof;
+    proof.trace_openings = positions.iter().map(|&p| QueryLayer { values: lde_row(p), siblings: trace_tree.path(p as u64).siblings }).collect();
+    proof.composition_openings = positions.iter().map(|&p| QueryLayer { values: vec![composition[p]], siblings: comp_tree.path(p as u64).siblings }).collect();
+    Ok(proof)
+}
+
+fn append_ood<F: PrimeField, E: ExtensionField<F>>(t: &mut Transcript, proof: &StarkProof<F, E>) {
+    t.append_ext(b"trace at z", &proof.trace_at_z);
+    t.append_ext(b"trace at next z", &proof.trace_at_next_z);
+    t.append_ext(b"composition at z", &[proof.composition_at_z]);
+}
+
+pub fn verify<F: PrimeField, E: ExtensionField<F>>(config: &FriConfig, perm: &Poseidon<F>, air: &Air<F>, proof: &StarkProof<F, E>) -> Result<(), StarkError> {
+    air.check_shape().map_err(StarkError::Air)?;
+    let w = air.width;
+    if proof.trace_at_z.len() != w || proof.trace_at_next_z.len() != w {
+        return Err(StarkError::Shape);
+    }
+    let (rows, q_bound) = (air.num_rows, composition_bound(air));
+    let n = q_bound * config.blowup();
+    let mut t = start_transcript(air);
+    t.append_fields(b"trace root", &proof.trace_root);
+    let alphas: Vec<E> = t.challenges_ext(b"constraint coefficients", air.transitions.len() + air.boundaries.len());
+    t.append_fields(b"composition root", &proof.composition_root);
+    let z: E = ood_point(&mut t, rows, n);
+    let next_z = z * E::from_base(root_of_unity::<F>(rows.trailing_zeros()));
+    // ood_point keeps z off the trace domain; None is still an error rather than a panic
+    if composition_at(air, &alphas, &proof.trace_at_z, &proof.trace_at_next_z, z) != Some(proof.composition_at_z) {
+        return Err(StarkError::OutOfDomain);
+    }
+    append_ood(&mut t, proof);
+
+    let gammas: Vec<E> = t.challenges_ext(b"deep coefficients", 2 * w + 1);
+    let opened = fri::verify(config, perm, q_bound, &proof.fri, &mut t).map_err(StarkError::Fri)?;
+    if proof.trace_openings.len() != opened.len() || proof.composition_openings.len() != opened.len() {
+        return Err(StarkError::Shape);
+    }
+    for (q, ((p, value), (row, comp))) in opened.iter().zip(proof.trace_openings.iter().zip(&proof.composition_openings)).enumerate() {
+        if row.values.len() != w || comp.values.len() != 1 {
+            return Err(StarkError::Shape);
+        }
+        let path = |siblings: &[Digest<F>]| DigestPath { index: *p as u64, siblings: siblings.to_vec() };
+        if path(&row.siblings).compute_root(perm, leaf_hash(perm, &row.values)) != proof.trace_root
+            || path(&comp.siblings).compute_root(perm, leaf_hash(perm, &comp.values)) != proof.composition_root
+        {
+            return Err(StarkError::Merkle { query: q });
+        }
+        if deep_at(proof, &gammas, &row.values, comp.values[0], domain_point(n, *p), z, next_z) != Some(*value) {
+            return Err(StarkError::Deep { query: q });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{poseidon_round_air, poseidon_round_trace, Expr};
+    use crate::{Goldilocks, GoldilocksExt2};
+
+    type E = GoldilocksExt2;
+
+    fn f(x: u64) -> Goldilocks { Goldilocks::from_u64(x) }
+
+    fn config() -> FriConfig { FriConfig { log_blowup: 2, log_arity: 2, num_queries: 16, grinding_bits: 4, final_poly_len: 4 } }
+
+    fn fibonacci_air() -> Air<Goldilocks> {
+        Air::new(2, 16)
+            .transition(Expr::next(0) - Expr::cur(1))
+            .transition(Expr::next(1) - Expr::cur(0) - Expr::cur(1))
+            .boundary(0, 0, f(1)).boundary(1, 0, f(1)).boundary(1, 15, f(1597))
+    }
+
+    #[test]
+    fn fibonacci() {
+        // (a, b) -> (b, a + b), 16 rows
+        let mut rows = vec![vec![f(1), f(1)]];
+        for i in 0..15 {
+            let (a, b) = (rows[i][0], rows[i][1]);
+            rows.push(vec![b, a + b]);
+        }
+        let air = fibonacci_air();
+        let perm = Poseidon::goldilocks_t8();
+        let proof: StarkProof<Goldilocks, E> = prove(&config(), &perm, &air, &TraceTable::from_rows(&rows)).unwrap();
+        assert_eq!(verify(&config(), &perm, &air, &proof), Ok(()));
+        let wrong = Air { boundaries: vec![crate::air::Boundary { column: 1, row: 15, value: f(1598) }], ..air.clone() };
+        assert_eq!(verify(&config(), &perm, &wrong, &proof), Err(StarkError::OutOfDomain));
+        // base-field challenges still work (at base-field soundness)
+        let proof: StarkProof<Goldilocks> = prove(&config(), &perm, &air, &TraceTable::from_rows(&rows)).unwrap();
+        assert_eq!(verify(&config(), &perm, &air, &proof), Ok(()));
+    }
+
+    #[test]
+    fn poles_are_reported_not_panicked() {
+        let air = fibonacci_air();
+        let alphas = vec![E::ONE; 5];
+        let row = vec![E::ONE; 2];
+        // x in the trace domain, and x on a boundary row
+        let omega = root_of_unity::<Goldilocks>(4);
+        assert_eq!(composition_at(&air, &alphas, &row, &row, E::from(omega.pow(3))), None);
+        assert_eq!(composition_at(&air, &alphas, &row, &row, E::ONE), None);
+        assert!(composition_at(&air, &alphas, &row, &row, E::new([f(3), f(5)])).is_some());
+        let z: E = ood_point(&mut Transcript::new(b"ood"), 16, 128);
+        assert_ne!(z.exp(16), E::ONE);
+    }
+
+    #[test]
+    fn iterated_poseidon_round() {
+        let perm = Poseidon::goldilocks_t8();
+        let input = [f(1), f(2), f(3)];
+        let trace = poseidon_round_trace(16, input);
+        let output: [Goldilocks; 3] = trace.row(15).try_into().unwrap();
+        let air = poseidon_round_air(16, input, output);
+        let proof: StarkProof<Goldilocks, E> = prove(&config(), &perm, &air, &trace).unwrap();
+        assert_eq!(verify(&config(), &perm, &air, &proof), Ok(()));
+
+        // the claimed output is bound by the boundary constraints
+        let other = poseidon_round_air(16, input, [output[0] + f(1), output[1], output[2]]);
+        assert!(verify(&config(), &perm, &other, &proof).is_err());
+        assert!(matches!(prove::<Goldilocks, E>(&config(), &perm, &other, &trace), Err(StarkError::Air(_))));
+
+        let mut bad = proof.clone();
+        bad.trace_openings[2].values[1] += f(1);
+        assert_eq!(verify(&config(), &perm, &air, &bad), Err(StarkError::Merkle { query: 2 }));
+        let mut bad = proof.clone();
+        bad.composition_openings[4].siblings[0][3] += f(1);
+        assert_eq!(verify(&config(), &perm, &air, &bad), Err(StarkError::Merkle { query: 4 }));
+        let mut bad = proof.clone();
+        bad.trace_at_next_z[0] += E::ONE;
+        assert_eq!(verify(&config(), &perm, &air, &bad), Err(StarkError::OutOfDomain));
+        let mut bad = proof.clone();
+        bad.composition_at_z += E::ONE;
+        assert!(verify(&config(), &perm, &air, &bad).is_err());
+    }
+}
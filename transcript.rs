@@ -0,0 +1,150 @@
+// transcript.rs — Fiat–Shamir transcript over SHAKE256.
+//
+// The transcript keeps a 32-byte running digest. Every message is absorbed as
+// digest = SHAKE256(digest || len(label) || label || len(data) || data), and a challenge is
+// squeezed as NUM_BYTES + 16 bytes of SHAKE256(digest || "challenge" || label), reduced mod
+// p (bias < 2^-128), which are then absorbed so later challenges depend on it. Byte-based
+// hashing keeps one transcript usable across fields (Goldilocks, curve scalar fields).
+
+use crate::field::{limbs_to_field, ExtensionField, PrimeField};
+use crate::keccak::shake256;
+
+#[derive(Clone, Debug)]
+pub struct Transcript {
+    state: [u8; 32],
+}
+
+impl Transcript {
+    pub fn new(protocol_label: &[u8]) -> Self {
+        let mut t = Self { state: [0; 32] };
+        t.append_bytes(b"protocol", protocol_label);
+        t
+    }
+
+    pub fn append_bytes(&mut self, label: &[u8], data: &[u8]) {
+        let mut input = self.state.to_vec();
+        for part in [label, data] {
+            input.extend((part.len() as u64).to_le_bytes());
+            input.extend(part);
+        }
+        self.state.copy_from_slice(&shake256(&input, 32));
+    }
+
+    pub fn append_field<F: PrimeField>(&mut self, label: &[u8], x: &F) {
+        self.append_bytes(label, &x.to_bytes_le());
+    }
+
+    pub fn append_fields<F: PrimeField>(&mut self, label: &[u8], xs: &[F]) {
+        let bytes: Vec<u8> = xs.iter().flat_map(|x| x.to_bytes_le()).collect();
+        self.append_bytes(label, &bytes);
+    }
+
+    fn squeeze(&mut self, label: &[u8], len: usize) -> Vec<u8> {
+        let mut input = self.state.to_vec();
+        input.extend(b"challenge");
+        input.extend(label);
+        let bytes = shake256(&input, len);
+        self.append_bytes(b"challenge", &bytes);
+        bytes
+    }
+
+    pub fn challenge<F: PrimeField>(&mut self, label: &[u8]) -> F {
+        let bytes = self.squeeze(label, F::NUM_BYTES + 16);
+        let limbs: Vec<u64> = bytes.chunks(8).map(|c| {
+            let mut l = [0u8; 8];
+            l[..c.len()].copy_from_slice(c);
+            u64::from_le_bytes(l)
+        }).collect();
+        limbs_to_field(&limbs)
+    }
+
+    pub fn challenges<F: PrimeField>(&mut self, label: &[u8], n: usize) -> Vec<F> {
+        (0..n).map(|_| self.challenge(label)).collect()
+    }
+
+    // extension elements, absorbed and sampled coordinate-wise
+    pub fn append_ext<F: PrimeField, E: ExtensionField<F>>(&mut self, label: &[u8], xs: &[E]) {
+        let coords: Vec<F> = xs.iter().flat_map(|x| x.to_base_coeffs()).collect();
+        self.append_fields(label, &coords);
+    }
+
+    pub fn challenge_ext<F: PrimeField, E: ExtensionField<F>>(&mut self, label: &[u8]) -> E {
+        E::from_base_coeffs(&self.challenges(label, E::DEGREE))
+    }
+
+    pub fn challenges_ext<F: PrimeField, E: ExtensionField<F>>(&mut self, label: &[u8], n: usize) -> Vec<E> {
+        (0..n).map(|_| self.challenge_ext(label)).collect()
+    }
+
+    // uniform index in [0, n): 64-bit samples at or above `zone` (a multiple of n) are
+    // rejected and redrawn, so the reduction is unbiased
+    pub fn challenge_index(&mut self, label: &[u8], n: usize) -> usize {
+        assert!(n > 0, "challenge index from an empty range");
+        let n = n as u64;
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = u64::from_le_bytes(self.squeeze(label, 8).try_into().unwrap());
+            if x < zone { return (x % n) as usize; }
+        }
+    }
+
+    // Proof of work: the first nonce for which SHAKE256(digest || "grind" || nonce) starts
+    // with `bits` zero bits. The nonce is not absorbed; callers append it.
+    pub fn grind(&self, bits: u32) -> u64 {
+        // the check reads 64 bits, so more could never be satisfied
+        assert!(bits <= 64, "cannot grind {bits} > 64 bits");
+        (0..).find(|n| self.check_grinding(bits, *n)).unwrap()
+    }
+
+    pub fn check_grinding(&self, bits: u32, nonce: u64) -> bool {
+        let mut input = self.state.to_vec();
+        input.extend(b"grind");
+        input.extend(nonce.to_le_bytes());
+        u64::from_be_bytes(shake256(&input, 8).try_into().unwrap()).leading_zeros() >= bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Goldilocks;
+
+    #[test]
+    fn challenges_depend_on_everything_absorbed() {
+        let run = |label: &[u8], x: u64| {
+            let mut t = Transcript::new(b"test");
+            t.append_field(label, &Goldilocks::from_u64(x));
+            let a: Goldilocks = t.challenge(b"a");
+            let b: Goldilocks = t.challenge(b"a");
+            (a, b)
+        };
+        let (a, b) = run(b"x", 1);
+        assert_ne!(a, b);
+        assert_eq!(run(b"x", 1), (a, b));
+        assert_ne!(run(b"x", 2).0, a);
+        assert_ne!(run(b"y", 1).0, a);
+    }
+
+    #[test]
+    fn grinding() {
+        let t = Transcript::new(b"test");
+        let nonce = t.grind(8);
+        assert!(t.check_grinding(8, nonce));
+        assert!((0..nonce).all(|n| !t.check_grinding(8, n)));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot grind")]
+    fn grinding_beyond_64_bits_is_rejected() {
+        Transcript::new(b"test").grind(65);
+    }
+
+    #[test]
+    fn challenge_indices() {
+        let mut t = Transcript::new(b"test");
+        assert_eq!(t.challenge_index(b"i", 1), 0);
+        for n in [3, 1000, usize::MAX / 2 + 2] {
+            assert!((0..50).all(|_| t.challenge_index(b"i", n) < n));
+        }
+    }
+}